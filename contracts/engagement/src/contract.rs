@@ -3,10 +3,15 @@ use soroban_sdk::{
 };
 use soroban_sdk::token::Client as TokenClient;
 
-use crate::storage::types::{Escrow, Milestone};
+use crate::storage::types::{
+    Escrow, EscrowBalance, EscrowPage, EscrowStatus, PendingRelease, ProtocolConfig, Role, SupportedAsset,
+    WasmHashEntry,
+};
 use crate::error::ContractError;
-use crate::events::{balance_retrieved_event};
-use crate::core::{EscrowManager, MilestoneManager, DisputeManager, UserManager};
+use crate::core::{
+    AssetManager, EscrowManager, FactoryManager, MilestoneManager, DisputeManager, MigrationManager, ProtocolManager,
+    RetainerManager, StreamManager, UserManager,
+};
 
 #[contract]
 pub struct EngagementContract;
@@ -21,18 +26,149 @@ impl EngagementContract {
         salt: BytesN<32>,
         init_fn: Symbol,
         init_args: Vec<Val>,
-    ) -> (Address, Val) {
-        if deployer != env.current_contract_address() {
-            deployer.require_auth();
-        }
+    ) -> Result<(Address, Val), ContractError> {
+        FactoryManager::deploy(env, deployer, wasm_hash, salt, init_fn, init_args)
+    }
 
-        let deployed_address = env
-            .deployer()
-            .with_address(deployer, salt)
-            .deploy(wasm_hash);
+    pub fn deploy_escrow(e: Env, escrow_properties: Escrow) -> Result<Address, ContractError> {
+        FactoryManager::deploy_escrow(e, escrow_properties)
+    }
+
+    pub fn pause_deployed_escrow(e: Env, engagement_id: String, caller: Address) -> Result<(), ContractError> {
+        FactoryManager::pause_deployed_escrow(e, engagement_id, caller)
+    }
+
+    pub fn unpause_deployed_escrow(e: Env, engagement_id: String) -> Result<(), ContractError> {
+        FactoryManager::unpause_deployed_escrow(e, engagement_id)
+    }
+
+    pub fn upgrade_deployed_escrow(
+        e: Env,
+        engagement_id: String,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), ContractError> {
+        FactoryManager::upgrade_deployed_escrow(e, engagement_id, new_wasm_hash)
+    }
+
+    pub fn migrate_deployed_escrow(
+        e: Env,
+        engagement_id: String,
+        engagement_ids: Vec<String>,
+        usdc_contract: Address,
+    ) -> Result<(), ContractError> {
+        FactoryManager::migrate_deployed_escrow(e, engagement_id, engagement_ids, usdc_contract)
+    }
+
+    pub fn register_wasm_hash(e: Env, wasm_hash: BytesN<32>, version: u32) -> Result<(), ContractError> {
+        FactoryManager::register_wasm_hash(e, wasm_hash, version)
+    }
 
-        let res: Val = env.invoke_contract(&deployed_address, &init_fn, init_args);
-        (deployed_address, res)
+    pub fn revoke_wasm_hash(e: Env, wasm_hash: BytesN<32>) -> Result<(), ContractError> {
+        FactoryManager::revoke_wasm_hash(e, wasm_hash)
+    }
+
+    pub fn get_approved_wasm_hashes(e: Env) -> Vec<WasmHashEntry> {
+        FactoryManager::get_approved_wasm_hashes(e)
+    }
+
+    pub fn get_wasm_hash_version(e: Env, wasm_hash: BytesN<32>) -> Option<u32> {
+        FactoryManager::get_wasm_hash_version(e, wasm_hash)
+    }
+
+    pub fn get_escrow_wasm_hash(e: Env) -> Result<WasmHashEntry, ContractError> {
+        FactoryManager::get_escrow_wasm_hash(e)
+    }
+
+    pub fn get_deployed_escrow(e: Env, engagement_id: String) -> Result<Address, ContractError> {
+        FactoryManager::get_deployed_escrow(e, engagement_id)
+    }
+
+    ////////////////////////
+    // Administration /////
+    ////////////////////////
+
+    pub fn initialize(
+        e: Env,
+        admin: Address,
+        protocol_config: ProtocolConfig,
+    ) -> Result<(), ContractError> {
+        ProtocolManager::initialize(e, admin, protocol_config)
+    }
+
+    pub fn set_admin(e: Env, new_admin: Address) -> Result<(), ContractError> {
+        ProtocolManager::set_admin(e, new_admin)
+    }
+
+    pub fn transfer_admin(e: Env, new_admin: Address) -> Result<(), ContractError> {
+        ProtocolManager::transfer_admin(e, new_admin)
+    }
+
+    pub fn accept_admin(e: Env) -> Result<(), ContractError> {
+        ProtocolManager::accept_admin(e)
+    }
+
+    pub fn set_protocol_config(e: Env, protocol_config: ProtocolConfig) -> Result<(), ContractError> {
+        ProtocolManager::set_protocol_config(e, protocol_config)
+    }
+
+    pub fn upgrade(e: Env, new_wasm_hash: BytesN<32>) -> Result<(), ContractError> {
+        ProtocolManager::upgrade(e, new_wasm_hash)
+    }
+
+    pub fn migrate(
+        e: Env,
+        engagement_ids: Vec<String>,
+        usdc_contract: Address,
+    ) -> Result<(), ContractError> {
+        MigrationManager::migrate(e, engagement_ids, usdc_contract)
+    }
+
+    pub fn pause(e: Env, caller: Address) -> Result<(), ContractError> {
+        ProtocolManager::pause(e, caller)
+    }
+
+    pub fn unpause(e: Env) -> Result<(), ContractError> {
+        ProtocolManager::unpause(e)
+    }
+
+    pub fn set_guardian(e: Env, guardian: Option<Address>) -> Result<(), ContractError> {
+        ProtocolManager::set_guardian(e, guardian)
+    }
+
+    pub fn is_paused(e: Env) -> bool {
+        ProtocolManager::is_paused(e)
+    }
+
+    pub fn get_guardian(e: Env) -> Option<Address> {
+        ProtocolManager::get_guardian(e)
+    }
+
+    pub fn get_schema_version(e: Env) -> u32 {
+        ProtocolManager::get_schema_version(e)
+    }
+
+    pub fn get_admin(e: Env) -> Result<Address, ContractError> {
+        ProtocolManager::get_admin(e)
+    }
+
+    pub fn get_pending_admin(e: Env) -> Option<Address> {
+        ProtocolManager::get_pending_admin(e)
+    }
+
+    pub fn get_protocol_config(e: Env) -> Result<ProtocolConfig, ContractError> {
+        ProtocolManager::get_protocol_config(e)
+    }
+
+    pub fn add_supported_asset(e: Env, asset: Address, minimum_amount: i128) -> Result<(), ContractError> {
+        AssetManager::add_supported_asset(e, asset, minimum_amount)
+    }
+
+    pub fn remove_supported_asset(e: Env, asset: Address) -> Result<(), ContractError> {
+        AssetManager::remove_supported_asset(e, asset)
+    }
+
+    pub fn get_supported_assets(e: Env) -> Vec<SupportedAsset> {
+        AssetManager::get_supported_assets(e)
     }
 
     ////////////////////////
@@ -41,42 +177,21 @@ impl EngagementContract {
 
     pub fn initialize_escrow(
         e: Env,
-        engagement_id: String,
-        client: Address,
-        service_provider: Address,
-        platform_address: Address,
-        amount: i128,
-        platform_fee: i128,
-        milestones: Vec<Milestone>,
-        release_signer: Address,
-        dispute_resolver: Address,
+        escrow_properties: Escrow,
     ) -> Result<String, ContractError> {
-        EscrowManager::initialize_escrow(
-            e, 
-            engagement_id, 
-            client, 
-            service_provider, 
-            platform_address, 
-            amount, 
-            platform_fee, 
-            milestones, 
-            release_signer, 
-            dispute_resolver
-        )
+        EscrowManager::initialize_escrow(e, escrow_properties)
     }
     
     pub fn fund_escrow(
         e: Env, 
         engagement_id: String, 
         signer: Address, 
-        usdc_contract: Address, 
         amount_to_deposit: i128
     ) -> Result<(), ContractError> {
         EscrowManager::fund_escrow(
             e, 
             engagement_id, 
             signer, 
-            usdc_contract, 
             amount_to_deposit
         )
     }
@@ -85,48 +200,164 @@ impl EngagementContract {
         e: Env, 
         engagement_id: String, 
         release_signer: Address, 
-        usdc_contract: Address,
-        trustless_work_address: Address
     ) -> Result<(), ContractError> {
         EscrowManager::distribute_escrow_earnings(
             e, 
             engagement_id, 
             release_signer, 
-            usdc_contract,
-            trustless_work_address
         )
     }
 
+    pub fn request_release(
+        e: Env,
+        engagement_id: String,
+        release_signer: Address,
+    ) -> Result<(), ContractError> {
+        EscrowManager::request_release(e, engagement_id, release_signer)
+    }
+
+    pub fn execute_release(e: Env, engagement_id: String) -> Result<(), ContractError> {
+        EscrowManager::execute_release(e, engagement_id)
+    }
+
     pub fn change_escrow_properties(
+        e: Env,
+        escrow_properties: Escrow,
+    ) -> Result<(), ContractError> {
+        EscrowManager::change_escrow_properties(e, escrow_properties)
+    }
+
+    pub fn update_approver(
         e: Env,
         engagement_id: String,
         client: Address,
-        service_provider: Address,
-        platform_address: Address,
-        amount: i128,
-        platform_fee: i128,
-        milestones: Vec<Milestone>,
-        release_signer: Address,
-        dispute_resolver: Address,
+        new_approver: Address,
     ) -> Result<(), ContractError> {
-        EscrowManager::change_escrow_properties(
+        EscrowManager::update_approver(e, engagement_id, client, new_approver)
+    }
+
+    pub fn update_release_signer(
+        e: Env,
+        engagement_id: String,
+        current_release_signer: Address,
+        new_release_signer: Address,
+    ) -> Result<(), ContractError> {
+        EscrowManager::update_release_signer(
             e,
             engagement_id,
-            client,
-            service_provider,
-            platform_address,
-            amount,
-            platform_fee,
-            milestones,
-            release_signer,
-            dispute_resolver
+            current_release_signer,
+            new_release_signer
         )
     }
 
+    pub fn update_dispute_resolver(
+        e: Env,
+        engagement_id: String,
+        new_dispute_resolver: Address,
+    ) -> Result<(), ContractError> {
+        EscrowManager::update_dispute_resolver(e, engagement_id, new_dispute_resolver)
+    }
+
+    pub fn get_escrow_balance(e: Env, engagement_id: String) -> Result<EscrowBalance, ContractError> {
+        EscrowManager::get_escrow_balance(e, engagement_id)
+    }
+
+    pub fn get_escrows_by_client(e: Env, client: Address, start: u32, limit: u32) -> Vec<String> {
+        EscrowManager::get_escrows_by_role(e, client, Role::Client, start, limit)
+    }
+
+    pub fn get_escrows_by_service_provider(
+        e: Env,
+        service_provider: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<String> {
+        EscrowManager::get_escrows_by_role(e, service_provider, Role::ServiceProvider, start, limit)
+    }
+
+    pub fn get_escrows_by_platform(e: Env, platform_address: Address, start: u32, limit: u32) -> Vec<String> {
+        EscrowManager::get_escrows_by_role(e, platform_address, Role::Platform, start, limit)
+    }
+
+    pub fn get_escrows_by_role(
+        e: Env,
+        address: Address,
+        role: Role,
+        start: u32,
+        limit: u32,
+    ) -> Vec<String> {
+        EscrowManager::get_escrows_by_role(e, address, role, start, limit)
+    }
+
+    pub fn list_escrows(
+        e: Env,
+        start: u32,
+        limit: u32,
+        status_filter: Option<EscrowStatus>,
+    ) -> EscrowPage {
+        EscrowManager::list_escrows(e, start, limit, status_filter)
+    }
+
+    pub fn get_escrow_status(e: Env, engagement_id: String) -> Result<EscrowStatus, ContractError> {
+        EscrowManager::get_escrow_status(e, engagement_id)
+    }
+
+    pub fn bump_escrow(e: Env, engagement_id: String) -> Result<(), ContractError> {
+        EscrowManager::bump_escrow(e, engagement_id)
+    }
+
+    pub fn get_release_approvals(e: Env, engagement_id: String) -> Vec<Address> {
+        EscrowManager::get_release_approvals(e, engagement_id)
+    }
+
+    pub fn get_pending_release(e: Env, engagement_id: String) -> Option<PendingRelease> {
+        EscrowManager::get_pending_release(e, engagement_id)
+    }
+
     pub fn get_escrow_by_id(e: Env, engagement_id: String) -> Result<Escrow, ContractError> {
         EscrowManager::get_escrow_by_id(e, engagement_id)
     }
 
+    ////////////////////////
+    // Retainers /////
+    ////////////////////////
+
+    pub fn release_retainer_period(
+        e: Env,
+        engagement_id: String,
+        release_signer: Option<Address>,
+    ) -> Result<(), ContractError> {
+        RetainerManager::release_retainer_period(e, engagement_id, release_signer)
+    }
+
+    pub fn stop_retainer(e: Env, engagement_id: String) -> Result<(), ContractError> {
+        RetainerManager::stop_retainer(e, engagement_id)
+    }
+
+    ////////////////////////
+    // Streams /////
+    ////////////////////////
+
+    pub fn withdraw_vested(e: Env, engagement_id: String) -> Result<(), ContractError> {
+        StreamManager::withdraw_vested(e, engagement_id)
+    }
+
+    pub fn halt_stream(e: Env, engagement_id: String) -> Result<(), ContractError> {
+        StreamManager::halt_stream(e, engagement_id)
+    }
+
+    pub fn open_stream_dispute(e: Env, engagement_id: String) -> Result<(), ContractError> {
+        StreamManager::open_stream_dispute(e, engagement_id)
+    }
+
+    pub fn resolve_stream_dispute(e: Env, engagement_id: String, halt: bool) -> Result<(), ContractError> {
+        StreamManager::resolve_stream_dispute(e, engagement_id, halt)
+    }
+
+    pub fn get_vested_amount(e: Env, engagement_id: String) -> Result<i128, ContractError> {
+        StreamManager::get_vested_amount(e, engagement_id)
+    }
+
     ////////////////////////
     // Milestones /////
     ////////////////////////
@@ -152,14 +383,14 @@ impl EngagementContract {
         engagement_id: String,
         milestone_index: i128,
         new_flag: bool,
-        client: Address,
+        approver: Address,
     ) -> Result<(), ContractError> {
         MilestoneManager::change_milestone_flag(
             e,
             engagement_id,
             milestone_index,
             new_flag,
-            client
+            approver
         )
     }
 
@@ -170,16 +401,16 @@ impl EngagementContract {
     pub fn resolving_disputes(
         e: Env,
         engagement_id: String,
+        milestone_index: i128,
         dispute_resolver: Address,
-        usdc_contract: Address,
         client_funds: i128,
         service_provider_funds: i128
     ) -> Result<(), ContractError> {
         DisputeManager::resolving_disputes(
             e,
             engagement_id,
+            milestone_index,
             dispute_resolver,
-            usdc_contract,
             client_funds,
             service_provider_funds
         )
@@ -188,8 +419,10 @@ impl EngagementContract {
     pub fn change_dispute_flag(
         e: Env, 
         engagement_id: String,
+        milestone_index: i128,
+        signer: Address,
     ) -> Result<(), ContractError> {
-        DisputeManager::change_dispute_flag(e, engagement_id)
+        DisputeManager::change_dispute_flag(e, engagement_id, milestone_index, signer)
     }
 
     ////////////////////////
@@ -209,9 +442,8 @@ impl EngagementContract {
         UserManager::login(&e, user_address)
     }
 
-    pub fn get_balance(e: Env, address: Address, usdc_token_address: Address) {
+    pub fn get_balance(e: Env, address: Address, usdc_token_address: Address) -> i128 {
         let usdc_token = TokenClient::new(&e, &usdc_token_address);
-        let balance = usdc_token.balance(&address);
-        balance_retrieved_event(&e, address, usdc_token_address, balance);
+        usdc_token.balance(&address)
     }
 }
\ No newline at end of file
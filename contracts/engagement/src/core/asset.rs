@@ -0,0 +1,75 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::storage::types::{DataKey, SupportedAsset};
+use crate::storage::store::extend_instance;
+use crate::error::ContractError;
+use crate::events::{asset_removed, asset_supported};
+use crate::core::protocol::ProtocolManager;
+
+pub struct AssetManager;
+
+impl AssetManager {
+
+    /// Adds `asset` to the token contracts escrows can be created in, or
+    /// changes its minimum if it is already listed. Only assets that move
+    /// exactly the transferred amount should be listed.
+    pub fn add_supported_asset(e: Env, asset: Address, minimum_amount: i128) -> Result<(), ContractError> {
+        let admin = ProtocolManager::require_admin(&e)?;
+
+        if minimum_amount < 0 {
+            return Err(ContractError::NegativeAmountNotAllowed);
+        }
+
+        let mut supported_assets = Self::get_supported_assets(e.clone());
+        if let Some(index) = Self::find_asset(&supported_assets, &asset) {
+            supported_assets.remove(index);
+        }
+        supported_assets.push_back(SupportedAsset {
+            asset: asset.clone(),
+            minimum_amount,
+        });
+
+        e.storage().instance().set(&DataKey::SupportedAssets, &supported_assets);
+        extend_instance(&e);
+
+        asset_supported(&e, admin, asset, minimum_amount);
+
+        Ok(())
+    }
+
+    /// Existing escrows in `asset` keep working; only new escrows are refused.
+    pub fn remove_supported_asset(e: Env, asset: Address) -> Result<(), ContractError> {
+        let admin = ProtocolManager::require_admin(&e)?;
+
+        let mut supported_assets = Self::get_supported_assets(e.clone());
+        let index = Self::find_asset(&supported_assets, &asset)
+            .ok_or(ContractError::AssetNotSupported)?;
+        supported_assets.remove(index);
+
+        e.storage().instance().set(&DataKey::SupportedAssets, &supported_assets);
+        extend_instance(&e);
+
+        asset_removed(&e, admin, asset);
+
+        Ok(())
+    }
+
+    pub fn get_supported_assets(e: Env) -> Vec<SupportedAsset> {
+        e.storage()
+            .instance()
+            .get(&DataKey::SupportedAssets)
+            .unwrap_or(Vec::new(&e))
+    }
+
+    pub fn get_supported_asset(e: Env, asset: Address) -> Option<SupportedAsset> {
+        let supported_assets = Self::get_supported_assets(e);
+        Self::find_asset(&supported_assets, &asset).and_then(|index| supported_assets.get(index))
+    }
+
+    fn find_asset(supported_assets: &Vec<SupportedAsset>, asset: &Address) -> Option<u32> {
+        supported_assets
+            .iter()
+            .position(|entry| entry.asset == *asset)
+            .map(|index| index as u32)
+    }
+}
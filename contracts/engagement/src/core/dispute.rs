@@ -1,10 +1,13 @@
-use soroban_sdk::{Address, Env, String};
+use soroban_sdk::{Address, Env, String, Vec};
 use soroban_sdk::token::Client as TokenClient;
 
-use crate::storage::types::DataKey;
+use crate::storage::types::{DataKey, Escrow, Milestone};
+use crate::storage::store::{remove_persistent, write_escrow};
 use crate::error::ContractError;
-use crate::events::escrows_by_engagement_id;
+use crate::events::{dispute_opened, dispute_resolved};
+use crate::events::types::{DisputeOpenedEvent, DisputeResolvedEvent};
 use crate::core::escrow::EscrowManager;
+use crate::core::protocol::ProtocolManager;
 
 pub struct DisputeManager;
 
@@ -13,81 +16,154 @@ impl DisputeManager {
     pub fn resolving_disputes(
         e: Env,
         engagement_id: String,
+        milestone_index: i128,
         dispute_resolver: Address,
-        usdc_contract: Address,
         client_funds: i128,
         service_provider_funds: i128
     ) -> Result<(), ContractError> {
+        ProtocolManager::require_not_paused(&e)?;
+
         dispute_resolver.require_auth();
     
-        let escrow_key = DataKey::Escrow(engagement_id.clone());
-        let escrow_result = EscrowManager::get_escrow_by_id(e.clone(), engagement_id.clone());
-    
-        let escrow = match escrow_result {
-            Ok(esc) => esc,
-            Err(err) => return Err(err),
-        };
+        let escrow = EscrowManager::get_escrow_by_id(e.clone(), engagement_id.clone())?;
     
         if dispute_resolver != escrow.dispute_resolver {
             return Err(ContractError::OnlyDisputeResolverCanExecuteThisFunction);
         }
+
+        if milestone_index < 0 || milestone_index >= escrow.milestones.len() as i128 {
+            return Err(ContractError::InvalidMileStoneIndex);
+        }
+
+        let disputed_milestone = escrow
+            .milestones
+            .get(milestone_index as u32)
+            .ok_or(ContractError::InvalidMileStoneIndex)?;
     
-        if !escrow.dispute_flag {
-            return Err(ContractError::EscrowNotInDispute);
+        if !disputed_milestone.dispute_flag {
+            return Err(ContractError::MilestoneNotInDispute);
+        }
+
+        if client_funds < 0 || service_provider_funds < 0 {
+            return Err(ContractError::ResolutionAmountsMustMatchMilestoneAmount);
         }
- 
-        let usdc_client = TokenClient::new(&e, &usdc_contract);
-        let contract_balance = usdc_client.balance(&e.current_contract_address());
 
-        let total_funds = client_funds + service_provider_funds;
-        if total_funds > contract_balance {
+        let total_funds = client_funds
+            .checked_add(service_provider_funds)
+            .ok_or(ContractError::ResolutionAmountsMustMatchMilestoneAmount)?;
+        if total_funds != disputed_milestone.amount {
+            return Err(ContractError::ResolutionAmountsMustMatchMilestoneAmount);
+        }
+ 
+        let escrow_balance = EscrowManager::get_escrow_balance(e.clone(), engagement_id.clone())?;
+        if total_funds > escrow_balance.outstanding {
             return Err(ContractError::InsufficientFundsForResolution);
         }
     
-        if client_funds > 0 {
-            usdc_client.transfer(
-                &e.current_contract_address(),
-                &escrow.client,
-                &(client_funds as i128)
-            );
-        }
+        let asset_client = TokenClient::new(&e, &escrow.asset);
 
-        if service_provider_funds > 0 {
-            usdc_client.transfer(
-                &e.current_contract_address(),
-                &escrow.service_provider,
-                &(service_provider_funds as i128)
-            );
+        EscrowManager::pay_out(&e, &asset_client, &escrow.client, client_funds)?;
+        EscrowManager::pay_out(&e, &asset_client, &escrow.service_provider, service_provider_funds)?;
+
+        // The disputed milestone is settled by the split above and must not be paid out again.
+        let mut updated_milestones = Vec::<Milestone>::new(&e);
+        for (index, milestone) in escrow.milestones.iter().enumerate() {
+            let mut new_milestone = milestone.clone();
+            if index as i128 == milestone_index {
+                new_milestone.dispute_flag = false;
+                new_milestone.released = true;
+            }
+            updated_milestones.push_back(new_milestone);
         }
+
+        let updated_escrow = Escrow {
+            milestones: updated_milestones,
+            ..escrow
+        };
     
-        e.storage().instance().set(&escrow_key, &escrow);
+        write_escrow(&e, &updated_escrow);
+        EscrowManager::record_balance_movement(
+            &e,
+            engagement_id.clone(),
+            0,
+            service_provider_funds,
+            client_funds
+        );
     
-        escrows_by_engagement_id(&e, engagement_id, escrow);
+        dispute_resolved(
+            &e,
+            &updated_escrow,
+            DisputeResolvedEvent {
+                milestone_index,
+                client_funds,
+                service_provider_funds,
+            },
+        );
     
         Ok(())
     }
 
+    /// Opened by the client or the service provider. The milestone is held
+    /// back from releases until the dispute resolver settles it.
     pub fn change_dispute_flag(
         e: Env, 
         engagement_id: String,
+        milestone_index: i128,
+        signer: Address,
     ) -> Result<(), ContractError> {
     
-        let escrow_key = DataKey::Escrow(engagement_id.clone());
-        let escrow_result = EscrowManager::get_escrow_by_id(e.clone(), engagement_id.clone());
-    
-        let mut escrow = match escrow_result {
-            Ok(esc) => esc,
-            Err(err) => return Err(err),
-        };
-    
-        if escrow.dispute_flag {
-            return Err(ContractError::EscrowAlreadyInDispute);
+        let escrow = EscrowManager::get_escrow_by_id(e.clone(), engagement_id.clone())?;
+
+        if signer != escrow.client && signer != escrow.service_provider {
+            return Err(ContractError::OnlyClientOrServiceProviderCanOpenDispute);
+        }
+
+        signer.require_auth();
+
+        if milestone_index < 0 || milestone_index >= escrow.milestones.len() as i128 {
+            return Err(ContractError::InvalidMileStoneIndex);
         }
+
+        let milestone = escrow
+            .milestones
+            .get(milestone_index as u32)
+            .ok_or(ContractError::InvalidMileStoneIndex)?;
     
-        escrow.dispute_flag = true;
-        e.storage().instance().set(&escrow_key, &escrow);
+        if milestone.dispute_flag {
+            return Err(ContractError::MilestoneAlreadyInDispute);
+        }
+
+        if milestone.released {
+            return Err(ContractError::MilestoneAlreadyReleased);
+        }
+
+        let mut updated_milestones = Vec::<Milestone>::new(&e);
+        for (index, milestone) in escrow.milestones.iter().enumerate() {
+            let mut new_milestone = milestone.clone();
+            if index as i128 == milestone_index {
+                new_milestone.dispute_flag = true;
+            }
+            updated_milestones.push_back(new_milestone);
+        }
+
+        let updated_escrow = Escrow {
+            milestones: updated_milestones,
+            ..escrow
+        };
+
+        write_escrow(&e, &updated_escrow);
+        // A disputed milestone drops out of the release the approvals were given for.
+        remove_persistent(&e, &DataKey::ReleaseApprovals(engagement_id.clone()));
     
-        escrows_by_engagement_id(&e, engagement_id, escrow);
+        dispute_opened(
+            &e,
+            engagement_id,
+            updated_escrow.dispute_resolver,
+            DisputeOpenedEvent {
+                milestone_index,
+                amount: milestone.amount,
+            },
+        );
     
         Ok(())
     }
@@ -1,9 +1,23 @@
 use soroban_sdk::{Address, Env, String, Vec};
 use soroban_sdk::token::Client as TokenClient;
 
-use crate::storage::types::{Escrow, Milestone, DataKey};
+use crate::storage::types::{
+    Escrow, EscrowBalance, EscrowMode, EscrowPage, EscrowStatus, Milestone, DataKey, PendingRelease,
+    ReleaseApprovals, Role, LEGACY_SCHEMA_VERSION, MAX_ESCROWS_SCANNED,
+};
+use crate::storage::store::{
+    add_escrow_to_index, bump_escrow_entries, index_escrow_participants,
+    read_escrow, read_escrow_count, read_escrow_id_at, read_escrows_by_role, read_persistent,
+    reindex_escrow_participants, remove_persistent, write_escrow, write_persistent,
+};
 use crate::error::ContractError;
-use crate::events::escrows_by_engagement_id;
+use crate::core::asset::AssetManager;
+use crate::core::protocol::ProtocolManager;
+use crate::core::validators::{validate_amount, validate_escrow_properties};
+use crate::events::{
+    escrow_amended, escrow_created, escrow_funded, funds_released, release_approved, release_requested,
+};
+use crate::events::types::{EscrowFundedEvent, FundsReleasedEvent};
 
 pub struct EscrowManager;
 
@@ -11,78 +25,83 @@ impl EscrowManager{
 
     pub fn initialize_escrow(
         e: Env,
-        engagement_id: String,
-        client: Address,
-        service_provider: Address,
-        platform_address: Address,
-        amount: i128,
-        platform_fee: i128,
-        milestones: Vec<Milestone>,
-        release_signer: Address,
-        dispute_resolver: Address,
+        escrow_properties: Escrow,
     ) -> Result<String, ContractError> {
+        escrow_properties.platform_address.require_auth();
 
-        if e.storage().instance().has(&DataKey::Admin) {
-            panic!("An escrow has already been initialized for this contract");
+        ProtocolManager::get_protocol_config(e.clone())?;
+
+        // Until its legacy escrow is migrated the contract still holds that
+        // escrow's funds, which new escrows must not be credited with.
+        if ProtocolManager::get_schema_version(e.clone()) == LEGACY_SCHEMA_VERSION {
+            return Err(ContractError::EscrowAlreadyInitialized);
         }
 
+        let engagement_id = escrow_properties.engagement_id.clone();
+        if e.storage().persistent().has(&DataKey::Escrow(engagement_id.clone())) {
+            return Err(ContractError::EscrowAlreadyInitialized);
+        }
 
-        if amount == 0 {
-            return Err(ContractError::AmountCannotBeZero);
+        let supported_asset = AssetManager::get_supported_asset(e.clone(), escrow_properties.asset.clone())
+            .ok_or(ContractError::AssetNotSupported)?;
+
+        validate_escrow_properties(&e, &escrow_properties, None)?;
+
+        if escrow_properties.amount < supported_asset.minimum_amount {
+            return Err(ContractError::AmountBelowAssetMinimum);
         }
 
-        let engagement_id_copy = engagement_id.clone();
-        let escrow = Escrow {
-            engagement_id: engagement_id.clone(),
-            client: client.clone(),
-            platform_address,
-            release_signer: release_signer.clone(),
-            service_provider: service_provider.clone(),
-            amount,
-            platform_fee: platform_fee,
-            milestones: milestones,
-            dispute_resolver: dispute_resolver.clone(),
-            dispute_flag: false,
+        let schedule_started = match &escrow_properties.mode {
+            EscrowMode::Milestones => false,
+            EscrowMode::Retainer(schedule) => schedule.periods_released != 0,
+            EscrowMode::Stream(schedule) => {
+                schedule.withdrawn != 0 || schedule.paused_at.is_some() || schedule.paused_duration != 0
+            }
         };
-        
-        e.storage().instance().set(&DataKey::Escrow(engagement_id.clone().into()), &escrow);
-        e.storage().instance().set(&DataKey::Admin, &true);
+        if schedule_started {
+            return Err(ContractError::InvalidEscrowSchedule);
+        }
+
+        write_escrow(&e, &escrow_properties);
+        add_escrow_to_index(&e, engagement_id.clone());
+        index_escrow_participants(&e, &escrow_properties);
 
-        Ok(engagement_id_copy)
+        escrow_created(&e, escrow_properties);
+
+        Ok(engagement_id)
     }
 
     pub fn fund_escrow(
         e: Env, 
         engagement_id: String, 
         signer: Address, 
-        usdc_contract: Address, 
         amount_to_deposit: i128
     ) -> Result<(), ContractError> {
+        ProtocolManager::require_not_paused(&e)?;
+
         signer.require_auth();
 
-        let escrow_key = DataKey::Escrow(engagement_id.clone());
-        let escrow_result = Self::get_escrow_by_id(e.clone(), engagement_id);
-    
-        let escrow = match escrow_result {
-            Ok(esc) => esc,
-            Err(err) => return Err(err),
-        };
+        validate_amount(amount_to_deposit)?;
 
-        if escrow.dispute_flag {
-            return Err(ContractError::EscrowOpenedForDisputeResolution);
-        }
+        let escrow = Self::get_escrow_by_id(e.clone(), engagement_id.clone())?;
     
-        let usdc_client = TokenClient::new(&e, &usdc_contract);
+        let asset_client = TokenClient::new(&e, &escrow.asset);
 
-        let signer_balance = usdc_client.balance(&signer);
+        let signer_balance = asset_client.balance(&signer);
 
         let contract_address = e.current_contract_address();
-        
-        if usdc_client.balance(&contract_address) as i128 > escrow.amount {
+
+        // The contract holds the funds of every escrow, so each one is checked
+        // against its own accounting rather than the contract's token balance.
+        let funded = Self::read_escrow_balance(&e, engagement_id.clone()).funded;
+        if funded >= escrow.amount {
             return Err(ContractError::EscrowFullyFunded);
         }
 
-        if amount_to_deposit as i128 > escrow.amount {
+        let total_funded = funded
+            .checked_add(amount_to_deposit)
+            .ok_or(ContractError::AmountToDepositGreatherThanEscrowAmount)?;
+        if total_funded > escrow.amount {
             return Err(ContractError::AmountToDepositGreatherThanEscrowAmount);
         }
 
@@ -90,136 +109,561 @@ impl EscrowManager{
             return Err(ContractError::SignerInsufficientFunds);
         }
 
-        usdc_client.transfer(&signer, &contract_address, &amount_to_deposit);
+        // Tokens that charge a fee on transfer deliver less than was sent, so
+        // the escrow is credited with what the contract actually received.
+        let balance_before = asset_client.balance(&contract_address);
+        asset_client.transfer(&signer, &contract_address, &amount_to_deposit);
+        let amount_received = asset_client.balance(&contract_address) - balance_before;
+
+        if amount_received <= 0 {
+            return Err(ContractError::AmountCannotBeZero);
+        }
     
-        e.storage().instance().set(&escrow_key, &escrow);
+        let balance = Self::record_balance_movement(&e, escrow.engagement_id.clone(), amount_received, 0, 0);
+
+        escrow_funded(
+            &e,
+            escrow.engagement_id,
+            signer,
+            EscrowFundedEvent {
+                amount: amount_received,
+                total_funded: balance.funded,
+            },
+        );
     
         Ok(())
     }
 
+    /// Kept for existing integrations; behaves exactly like `request_release`.
     pub fn distribute_escrow_earnings(
         e: Env, 
         engagement_id: String, 
         release_signer: Address, 
-        usdc_contract: Address,
-        trustless_work_address: Address
     ) -> Result<(), ContractError> {
+        Self::request_release(e, engagement_id, release_signer)
+    }
+
+    /// Records `release_signer`'s approval of releasing the approved
+    /// milestones. The approval that reaches the threshold pays them out
+    /// straight away, or starts the escrow's challenge period if it has one.
+    pub fn request_release(
+        e: Env,
+        engagement_id: String,
+        release_signer: Address,
+    ) -> Result<(), ContractError> {
+        ProtocolManager::require_not_paused(&e)?;
+
         release_signer.require_auth();
-        let escrow_key = DataKey::Escrow(engagement_id.clone());
-        let escrow_result = Self::get_escrow_by_id(e.clone(), engagement_id);
-        
-        let escrow = match escrow_result {
-            Ok(esc) => esc,
-            Err(err) => return Err(err),
-        };
+        let approvals_key = DataKey::ReleaseApprovals(engagement_id.clone());
+        let escrow = Self::get_escrow_by_id(e.clone(), engagement_id.clone())?;
         
-        if release_signer != escrow.release_signer {
+        if !escrow.release_signers.contains(&release_signer) {
             return Err(ContractError::OnlyReleaseSignerCanClaimEarnings);
         }
+
+        if escrow.mode != EscrowMode::Milestones {
+            return Err(ContractError::NotSupportedForEscrowMode);
+        }
     
         if escrow.milestones.is_empty() {
             return Err(ContractError::NoMileStoneDefined);
         }
+
+        if e.storage().persistent().has(&DataKey::PendingRelease(engagement_id.clone())) {
+            return Err(ContractError::ReleaseAlreadyRequested);
+        }
     
-        if !escrow.milestones.iter().all(|milestone| milestone.flag) {
+        // Only approved milestones that are neither disputed nor already paid are released,
+        // so a dispute on one milestone does not hold back the rest of the engagement.
+        let mut milestone_indexes = Vec::<u32>::new(&e);
+        for (index, milestone) in escrow.milestones.iter().enumerate() {
+            if milestone.flag && !milestone.released && !milestone.dispute_flag {
+                milestone_indexes.push_back(index as u32);
+            }
+        }
+
+        if milestone_indexes.is_empty() {
             return Err(ContractError::EscrowNotCompleted);
         }
-    
-        if escrow.dispute_flag {
-            return Err(ContractError::InvalidState);
+
+        // Approvals only count for the milestones they were given for, so they
+        // start over once that set of milestones changes.
+        let mut approvals = read_persistent::<ReleaseApprovals>(&e, &approvals_key)
+            .filter(|approvals| approvals.milestone_indexes == milestone_indexes)
+            .unwrap_or(ReleaseApprovals {
+                milestone_indexes: milestone_indexes.clone(),
+                signers: Vec::new(&e),
+            });
+        if approvals.signers.contains(&release_signer) {
+            return Err(ContractError::ReleaseAlreadyApprovedBySigner);
+        }
+        approvals.signers.push_back(release_signer.clone());
+
+        // Funds only move on the approval that reaches the threshold; earlier
+        // approvals are recorded and wait for the remaining signers.
+        if approvals.signers.len() < escrow.release_threshold {
+            write_persistent(&e, &approvals_key, &approvals);
+            release_approved(&e, engagement_id, release_signer, approvals);
+            return Ok(());
+        }
+
+        remove_persistent(&e, &approvals_key);
+
+        if escrow.challenge_period == 0 {
+            return Self::release_milestones(&e, escrow, &milestone_indexes);
+        }
+
+        let pending_release = PendingRelease {
+            milestone_indexes,
+            executable_at: e.ledger().timestamp() + escrow.challenge_period,
+        };
+        write_persistent(&e, &DataKey::PendingRelease(engagement_id.clone()), &pending_release);
+
+        release_requested(&e, engagement_id, release_signer, pending_release);
+
+        Ok(())
+    }
+
+    /// Pays out a requested release once its challenge period is over.
+    /// Milestones disputed in the meantime are left out and settled through
+    /// dispute resolution instead.
+    pub fn execute_release(e: Env, engagement_id: String) -> Result<(), ContractError> {
+        ProtocolManager::require_not_paused(&e)?;
+
+        let escrow = Self::get_escrow_by_id(e.clone(), engagement_id.clone())?;
+        let pending_key = DataKey::PendingRelease(engagement_id);
+        let pending_release: PendingRelease = read_persistent(&e, &pending_key)
+            .ok_or(ContractError::NothingToRelease)?;
+
+        if e.ledger().timestamp() < pending_release.executable_at {
+            return Err(ContractError::ReleaseNotYetDue);
+        }
+
+        remove_persistent(&e, &pending_key);
+
+        Self::release_milestones(&e, escrow, &pending_release.milestone_indexes)
+    }
+
+    pub fn get_pending_release(e: Env, engagement_id: String) -> Option<PendingRelease> {
+        read_persistent(&e, &DataKey::PendingRelease(engagement_id))
+    }
+
+    /// Pays out the milestones at `milestone_indexes` that are still approved,
+    /// undisputed and unpaid, and marks them released.
+    fn release_milestones(
+        e: &Env,
+        escrow: Escrow,
+        milestone_indexes: &Vec<u32>,
+    ) -> Result<(), ContractError> {
+        let mut total_amount: i128 = 0;
+        let mut updated_milestones = Vec::<Milestone>::new(e);
+        for (index, milestone) in escrow.milestones.iter().enumerate() {
+            let mut new_milestone = milestone.clone();
+            if milestone_indexes.contains(index as u32)
+                && milestone.flag
+                && !milestone.released
+                && !milestone.dispute_flag
+            {
+                total_amount += milestone.amount;
+                new_milestone.released = true;
+            }
+            updated_milestones.push_back(new_milestone);
+        }
+
+        if total_amount == 0 {
+            return Ok(());
         }
     
-        let usdc_client = TokenClient::new(&e, &usdc_contract);
-        let contract_address = e.current_contract_address();
-    
-        // Check the actual balance of the contract for this escrow
-        let contract_balance = usdc_client.balance(&contract_address);
-        if contract_balance < escrow.amount as i128 {
+        let escrow_balance = Self::read_escrow_balance(e, escrow.engagement_id.clone());
+        if escrow_balance.outstanding < total_amount {
             return Err(ContractError::EscrowBalanceNotSufficienteToSendEarnings);
         }
     
-        let platform_fee_percentage = escrow.platform_fee as i128;
-        let platform_address = escrow.platform_address.clone();
-    
-        let total_amount = escrow.amount as i128;
-        let trustless_work_commission = ((total_amount * 30) / 10000) as i128; 
-        let platform_commission = (total_amount * platform_fee_percentage) / 100 as i128;
-            
-        usdc_client.transfer(
-            &contract_address, 
-            &trustless_work_address, 
-            &trustless_work_commission
-        );
-    
-        usdc_client.transfer(
-            &contract_address, 
-            &platform_address, 
-            &platform_commission
-        );
-    
-        let service_provider_amount = total_amount - trustless_work_commission - platform_commission;
-    
-        usdc_client.transfer(
-            &contract_address, 
-            &escrow.service_provider, 
-            &service_provider_amount
-        );
+        let released = Self::pay_out_earnings(e, &escrow, total_amount)?;
+
+        let updated_escrow = Escrow {
+            milestones: updated_milestones,
+            ..escrow
+        };
     
-        e.storage().instance().set(&escrow_key, &escrow);
+        write_escrow(e, &updated_escrow);
+        Self::record_balance_movement(e, updated_escrow.engagement_id.clone(), 0, total_amount, 0);
+
+        funds_released(e, &updated_escrow, released);
     
         Ok(())
     }
 
     pub fn change_escrow_properties(
         e: Env,
-        engagement_id: String,
-        client: Address,
-        service_provider: Address,
-        platform_address: Address,
-        amount: i128,
-        platform_fee: i128,
-        milestones: Vec<Milestone>,
-        release_signer: Address,
-        dispute_resolver: Address,
+        escrow_properties: Escrow,
     ) -> Result<(), ContractError> {
+        ProtocolManager::require_not_paused(&e)?;
+
+        let engagement_id = escrow_properties.engagement_id.clone();
         let existing_escrow = Self::get_escrow_by_id(e.clone(), engagement_id.clone())?;
 
-        if platform_address != existing_escrow.platform_address {
+        if escrow_properties.platform_address != existing_escrow.platform_address {
             return Err(ContractError::OnlyPlatformAddressExecuteThisFunction);
         }
         
-        platform_address.require_auth();
+        escrow_properties.platform_address.require_auth();
 
+        // The asset and mode are fixed at creation since funds may already be
+        // held in the asset and released under the mode. The client and the
+        // service provider cannot be replaced by the platform, and the approver,
+        // release signers and dispute resolver only change through their own
+        // entry points, which need the consent of the parties. A stream vests
+        // its amount over time, so the amount of a stream is fixed as well.
+        let amount = match existing_escrow.mode {
+            EscrowMode::Stream(_) => existing_escrow.amount,
+            _ => escrow_properties.amount,
+        };
         let updated_escrow = Escrow {
-            engagement_id: engagement_id.clone(),
-            client,
-            platform_address,
-            release_signer,
-            service_provider,
+            client: existing_escrow.client.clone(),
+            service_provider: existing_escrow.service_provider.clone(),
+            approver: existing_escrow.approver.clone(),
+            asset: existing_escrow.asset.clone(),
+            release_signers: existing_escrow.release_signers.clone(),
+            release_threshold: existing_escrow.release_threshold,
+            dispute_resolver: existing_escrow.dispute_resolver.clone(),
+            mode: existing_escrow.mode.clone(),
+            challenge_period: existing_escrow.challenge_period,
             amount,
-            platform_fee,
+            ..escrow_properties
+        };
+
+        validate_escrow_properties(&e, &updated_escrow, Some(&existing_escrow))?;
+
+        // Once the escrow holds funds, the platform cannot change its own cut
+        // without the client, and deposits cannot end up above the amount.
+        let funded = Self::read_escrow_balance(&e, engagement_id.clone()).funded;
+        if funded > 0 {
+            if updated_escrow.amount < funded {
+                return Err(ContractError::AmountToDepositGreatherThanEscrowAmount);
+            }
+
+            if updated_escrow.platform_fee != existing_escrow.platform_fee {
+                existing_escrow.client.require_auth();
+            }
+        }
+
+        // An approval covers a milestone as it was approved, so it does not
+        // carry over to a milestone whose terms change
+        let mut milestones = Vec::<Milestone>::new(&e);
+        for (index, milestone) in updated_escrow.milestones.iter().enumerate() {
+            let mut new_milestone = milestone.clone();
+            if let Some(stored_milestone) = existing_escrow.milestones.get(index as u32) {
+                if milestone.description != stored_milestone.description || milestone.amount != stored_milestone.amount {
+                    new_milestone.flag = false;
+                }
+            }
+            milestones.push_back(new_milestone);
+        }
+        let updated_escrow = Escrow {
             milestones,
-            dispute_resolver,
-            dispute_flag: false,
+            ..updated_escrow
         };
 
-        e.storage().instance().set(
-            &DataKey::Escrow(engagement_id.into()),
-            &updated_escrow
-        );
+        if let Some(supported_asset) = AssetManager::get_supported_asset(e.clone(), existing_escrow.asset.clone()) {
+            if updated_escrow.amount < supported_asset.minimum_amount {
+                return Err(ContractError::AmountBelowAssetMinimum);
+            }
+        }
+
+        write_escrow(&e, &updated_escrow);
+        reindex_escrow_participants(&e, &existing_escrow, &updated_escrow);
+
+        // Pending approvals and releases were given for the previous terms
+        remove_persistent(&e, &DataKey::ReleaseApprovals(engagement_id.clone()));
+        remove_persistent(&e, &DataKey::PendingRelease(engagement_id));
 
-        escrows_by_engagement_id(&e, updated_escrow.engagement_id.clone(), updated_escrow);
+        escrow_amended(&e, updated_escrow.platform_address.clone(), updated_escrow);
 
         Ok(())
     }
 
-    pub fn get_escrow_by_id(e: Env, engagement_id: String) -> Result<Escrow, ContractError> {
-        let escrow_key = DataKey::Escrow(engagement_id.clone());
-        if let Some(escrow) = e.storage().instance().get::<DataKey, Escrow>(&escrow_key) {
-            escrows_by_engagement_id(&e, engagement_id.clone(), escrow.clone());
-            Ok(escrow)
+    pub fn update_approver(
+        e: Env,
+        engagement_id: String,
+        client: Address,
+        new_approver: Address,
+    ) -> Result<(), ContractError> {
+        ProtocolManager::require_not_paused(&e)?;
+
+        let existing_escrow = Self::get_escrow_by_id(e.clone(), engagement_id.clone())?;
+
+        if client != existing_escrow.client {
+            return Err(ContractError::OnlyClientCanChangeApprover);
+        }
+
+        client.require_auth();
+
+        let updated_escrow = Escrow {
+            approver: Some(new_approver),
+            ..existing_escrow.clone()
+        };
+
+        write_escrow(&e, &updated_escrow);
+        reindex_escrow_participants(&e, &existing_escrow, &updated_escrow);
+
+        escrow_amended(&e, client, updated_escrow);
+
+        Ok(())
+    }
+
+    pub fn update_release_signer(
+        e: Env,
+        engagement_id: String,
+        current_release_signer: Address,
+        new_release_signer: Address,
+    ) -> Result<(), ContractError> {
+        ProtocolManager::require_not_paused(&e)?;
+
+        let existing_escrow = Self::get_escrow_by_id(e.clone(), engagement_id.clone())?;
+
+        let signer_index = match existing_escrow.release_signers.first_index_of(&current_release_signer) {
+            Some(index) => index,
+            None => return Err(ContractError::ReleaseSignerNotFound),
+        };
+
+        if existing_escrow.release_signers.contains(&new_release_signer) {
+            return Err(ContractError::DuplicateReleaseSigner);
+        }
+
+        current_release_signer.require_auth();
+        existing_escrow.client.require_auth();
+        existing_escrow.service_provider.require_auth();
+
+        if Self::escrow_status(&e, &existing_escrow) == EscrowStatus::InDispute {
+            return Err(ContractError::EscrowOpenedForDisputeResolution);
+        }
+
+        let mut release_signers = existing_escrow.release_signers.clone();
+        release_signers.set(signer_index, new_release_signer);
+
+        let updated_escrow = Escrow {
+            release_signers,
+            ..existing_escrow.clone()
+        };
+
+        write_escrow(&e, &updated_escrow);
+        reindex_escrow_participants(&e, &existing_escrow, &updated_escrow);
+
+        // An approval given by the replaced signer no longer counts
+        remove_persistent(&e, &DataKey::ReleaseApprovals(engagement_id));
+
+        escrow_amended(&e, current_release_signer, updated_escrow);
+
+        Ok(())
+    }
+
+    pub fn update_dispute_resolver(
+        e: Env,
+        engagement_id: String,
+        new_dispute_resolver: Address,
+    ) -> Result<(), ContractError> {
+        ProtocolManager::require_not_paused(&e)?;
+
+        let existing_escrow = Self::get_escrow_by_id(e.clone(), engagement_id.clone())?;
+
+        existing_escrow.dispute_resolver.require_auth();
+        existing_escrow.client.require_auth();
+        existing_escrow.service_provider.require_auth();
+
+        if Self::escrow_status(&e, &existing_escrow) == EscrowStatus::InDispute {
+            return Err(ContractError::EscrowOpenedForDisputeResolution);
+        }
+
+        let current_dispute_resolver = existing_escrow.dispute_resolver.clone();
+        let updated_escrow = Escrow {
+            dispute_resolver: new_dispute_resolver,
+            ..existing_escrow.clone()
+        };
+
+        write_escrow(&e, &updated_escrow);
+        reindex_escrow_participants(&e, &existing_escrow, &updated_escrow);
+
+        escrow_amended(&e, current_dispute_resolver, updated_escrow);
+
+        Ok(())
+    }
+
+    pub fn get_escrows_by_role(
+        e: Env,
+        address: Address,
+        role: Role,
+        start: u32,
+        limit: u32,
+    ) -> Vec<String> {
+        read_escrows_by_role(&e, role, address, start, limit)
+    }
+
+    /// Pages through escrows in creation order, starting at position `start`.
+    /// A call looks at no more than `MAX_ESCROWS_SCANNED` escrows, so a
+    /// filtered page can come back short; `next_start` tells where to resume.
+    pub fn list_escrows(
+        e: Env,
+        start: u32,
+        limit: u32,
+        status_filter: Option<EscrowStatus>,
+    ) -> EscrowPage {
+        let escrow_count = read_escrow_count(&e);
+        let scan_end = start.saturating_add(MAX_ESCROWS_SCANNED).min(escrow_count);
+
+        let mut escrows = Vec::new(&e);
+        let mut index = start;
+        while index < scan_end && escrows.len() < limit {
+            let escrow = read_escrow_id_at(&e, index)
+                .and_then(|engagement_id| Self::get_escrow_by_id(e.clone(), engagement_id).ok());
+            index += 1;
+
+            if let Some(escrow) = escrow {
+                if status_filter.is_none() || status_filter == Some(Self::escrow_status(&e, &escrow)) {
+                    escrows.push_back(escrow);
+                }
+            }
+        }
+
+        EscrowPage {
+            escrows,
+            next_start: if index < escrow_count { Some(index) } else { None },
+        }
+    }
+
+    pub fn get_escrow_status(e: Env, engagement_id: String) -> Result<EscrowStatus, ContractError> {
+        let escrow = Self::get_escrow_by_id(e.clone(), engagement_id)?;
+        Ok(Self::escrow_status(&e, &escrow))
+    }
+
+    fn escrow_status(e: &Env, escrow: &Escrow) -> EscrowStatus {
+        let in_dispute = match &escrow.mode {
+            EscrowMode::Stream(schedule) => schedule.paused_at.is_some(),
+            _ => escrow.milestones.iter().any(|milestone| milestone.dispute_flag),
+        };
+        if in_dispute {
+            return EscrowStatus::InDispute;
+        }
+
+        let completed = match &escrow.mode {
+            EscrowMode::Milestones => {
+                !escrow.milestones.is_empty() && escrow.milestones.iter().all(|milestone| milestone.released)
+            }
+            EscrowMode::Retainer(schedule) => schedule.periods_released == schedule.period_count,
+            EscrowMode::Stream(schedule) => schedule.withdrawn == escrow.amount,
+        };
+        if completed {
+            return EscrowStatus::Completed;
+        }
+
+        if Self::read_escrow_balance(e, escrow.engagement_id.clone()).funded > 0 {
+            EscrowStatus::Funded
         } else {
-            return Err(ContractError::EscrowNotFound)
+            EscrowStatus::Initialized
+        }
+    }
+
+    pub fn bump_escrow(e: Env, engagement_id: String) -> Result<(), ContractError> {
+        let escrow = read_escrow(&e, engagement_id).ok_or(ContractError::EscrowNotFound)?;
+
+        bump_escrow_entries(&e, &escrow);
+
+        Ok(())
+    }
+
+    pub fn get_release_approvals(e: Env, engagement_id: String) -> Vec<Address> {
+        read_persistent::<ReleaseApprovals>(&e, &DataKey::ReleaseApprovals(engagement_id))
+            .map(|approvals| approvals.signers)
+            .unwrap_or(Vec::new(&e))
+    }
+
+    pub fn get_escrow_balance(e: Env, engagement_id: String) -> Result<EscrowBalance, ContractError> {
+        Self::get_escrow_by_id(e.clone(), engagement_id.clone())?;
+
+        Ok(Self::read_escrow_balance(&e, engagement_id))
+    }
+
+    fn read_escrow_balance(e: &Env, engagement_id: String) -> EscrowBalance {
+        read_persistent(e, &DataKey::EscrowBalance(engagement_id))
+            .unwrap_or(EscrowBalance {
+                funded: 0,
+                released: 0,
+                refunded: 0,
+                outstanding: 0,
+            })
+    }
+
+    /// Pays `amount` of the escrow to the service provider after taking the
+    /// Trustless Work and platform commissions out of it.
+    pub(crate) fn pay_out_earnings(
+        e: &Env,
+        escrow: &Escrow,
+        amount: i128,
+    ) -> Result<FundsReleasedEvent, ContractError> {
+        let asset_client = TokenClient::new(e, &escrow.asset);
+
+        let protocol_config = ProtocolManager::get_protocol_config(e.clone())?;
+        let trustless_work_commission = (amount * protocol_config.trustless_work_fee) / 10000;
+        let platform_commission = (amount * escrow.platform_fee) / 100;
+        let service_provider_amount = amount - trustless_work_commission - platform_commission;
+
+        // The protocol fee can be raised after the escrow was created.
+        if service_provider_amount < 0 {
+            return Err(ContractError::PlatformFeeTooHigh);
         }
+
+        Self::pay_out(e, &asset_client, &protocol_config.trustless_work_address, trustless_work_commission)?;
+        Self::pay_out(e, &asset_client, &escrow.platform_address, platform_commission)?;
+        Self::pay_out(e, &asset_client, &escrow.service_provider, service_provider_amount)?;
+
+        Ok(FundsReleasedEvent {
+            amount,
+            trustless_work_commission,
+            platform_commission,
+            service_provider_amount,
+        })
+    }
+
+    /// Sends `amount` of an escrow asset held by the contract to `payee`.
+    /// Stellar assets refuse accounts without a trustline and balances the
+    /// issuer has not authorized, so a failed transfer is reported as an
+    /// error instead of aborting the whole call.
+    pub(crate) fn pay_out(
+        e: &Env,
+        asset_client: &TokenClient,
+        payee: &Address,
+        amount: i128,
+    ) -> Result<(), ContractError> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        match asset_client.try_transfer(&e.current_contract_address(), payee, &amount) {
+            Ok(Ok(())) => Ok(()),
+            _ => Err(ContractError::PayeeCannotReceiveAsset),
+        }
+    }
+
+    pub(crate) fn record_balance_movement(
+        e: &Env,
+        engagement_id: String,
+        funded: i128,
+        released: i128,
+        refunded: i128,
+    ) -> EscrowBalance {
+        let mut balance = Self::read_escrow_balance(e, engagement_id.clone());
+
+        balance.funded += funded;
+        balance.released += released;
+        balance.refunded += refunded;
+        balance.outstanding = balance.funded - balance.released - balance.refunded;
+
+        write_persistent(e, &DataKey::EscrowBalance(engagement_id), &balance);
+
+        balance
+    }
+
+    pub fn get_escrow_by_id(e: Env, engagement_id: String) -> Result<Escrow, ContractError> {
+        read_escrow(&e, engagement_id).ok_or(ContractError::EscrowNotFound)
     }
 }
\ No newline at end of file
@@ -0,0 +1,236 @@
+use soroban_sdk::{xdr::ToXdr, Address, BytesN, Env, String, Symbol, Val, Vec};
+
+use crate::storage::types::{DataKey, Escrow, ProtocolConfig, SupportedAsset, WasmHashEntry};
+use crate::storage::store::{extend_instance, read_persistent, write_persistent};
+use crate::error::ContractError;
+use crate::events::{escrow_deployed, wasm_hash_registered, wasm_hash_revoked};
+use crate::core::asset::AssetManager;
+use crate::core::protocol::ProtocolManager;
+use crate::contract::EngagementContractClient;
+
+pub struct FactoryManager;
+
+impl FactoryManager {
+
+    /// Deploys `wasm_hash` and calls `init_fn` on the new contract. Only
+    /// hashes on the approved list can be deployed. Addresses derived from
+    /// this contract are where `deploy_escrow` puts escrow instances, so only
+    /// the admin can deploy under them.
+    pub fn deploy(
+        e: Env,
+        deployer: Address,
+        wasm_hash: BytesN<32>,
+        salt: BytesN<32>,
+        init_fn: Symbol,
+        init_args: Vec<Val>,
+    ) -> Result<(Address, Val), ContractError> {
+        ProtocolManager::require_not_paused(&e)?;
+
+        if deployer == e.current_contract_address() {
+            ProtocolManager::require_admin(&e)?;
+        } else {
+            deployer.require_auth();
+        }
+
+        if Self::get_wasm_hash_version(e.clone(), wasm_hash.clone()).is_none() {
+            return Err(ContractError::WasmHashNotApproved);
+        }
+
+        let deployed_address = e
+            .deployer()
+            .with_address(deployer, salt)
+            .deploy(wasm_hash);
+
+        let res: Val = e.invoke_contract(&deployed_address, &init_fn, init_args);
+        Ok((deployed_address, res))
+    }
+
+    /// Deploys a dedicated escrow instance for `escrow_properties` from the
+    /// latest approved wasm and initializes it. The instance is administered
+    /// by this contract and inherits its protocol configuration.
+    pub fn deploy_escrow(e: Env, escrow_properties: Escrow) -> Result<Address, ContractError> {
+        ProtocolManager::require_not_paused(&e)?;
+
+        escrow_properties.platform_address.require_auth();
+
+        let engagement_id = escrow_properties.engagement_id.clone();
+        let deployed_key = DataKey::DeployedEscrow(engagement_id.clone());
+        if e.storage().persistent().has(&deployed_key) {
+            return Err(ContractError::EscrowAlreadyInitialized);
+        }
+
+        let supported_asset = AssetManager::get_supported_asset(e.clone(), escrow_properties.asset.clone())
+            .ok_or(ContractError::AssetNotSupported)?;
+
+        let wasm_hash = Self::get_escrow_wasm_hash(e.clone())?.wasm_hash;
+        let protocol_config = ProtocolManager::get_protocol_config(e.clone())?;
+
+        let deployed_address = e
+            .deployer()
+            .with_current_contract(Self::escrow_salt(&e, engagement_id.clone()))
+            .deploy(wasm_hash);
+
+        Self::initialize_deployed_escrow(&e, &deployed_address, &protocol_config, &supported_asset, &escrow_properties);
+
+        write_persistent(&e, &deployed_key, &deployed_address);
+
+        escrow_deployed(&e, engagement_id, escrow_properties.platform_address, deployed_address.clone());
+
+        Ok(deployed_address)
+    }
+
+    /// Sets up a freshly deployed instance with this contract as its admin.
+    fn initialize_deployed_escrow(
+        e: &Env,
+        deployed_address: &Address,
+        protocol_config: &ProtocolConfig,
+        supported_asset: &SupportedAsset,
+        escrow_properties: &Escrow,
+    ) {
+        let escrow_client = EngagementContractClient::new(e, deployed_address);
+        escrow_client.initialize(&e.current_contract_address(), protocol_config);
+        escrow_client.add_supported_asset(&supported_asset.asset, &supported_asset.minimum_amount);
+        escrow_client.initialize_escrow(escrow_properties);
+    }
+
+    // Deployed instances are administered by this contract, so the calls
+    // below are how the admin (or the guardian, for pausing) reaches them.
+
+    pub fn pause_deployed_escrow(e: Env, engagement_id: String, caller: Address) -> Result<(), ContractError> {
+        let admin = ProtocolManager::get_admin(e.clone())?;
+        if caller != admin && Some(caller.clone()) != ProtocolManager::get_guardian(e.clone()) {
+            return Err(ContractError::OnlyAdminOrGuardianCanPause);
+        }
+
+        caller.require_auth();
+
+        Self::deployed_escrow_client(&e, engagement_id)?.pause(&e.current_contract_address());
+
+        Ok(())
+    }
+
+    pub fn unpause_deployed_escrow(e: Env, engagement_id: String) -> Result<(), ContractError> {
+        ProtocolManager::require_admin(&e)?;
+
+        Self::deployed_escrow_client(&e, engagement_id)?.unpause();
+
+        Ok(())
+    }
+
+    /// Upgrades a deployed instance. Only hashes on the approved list can be
+    /// installed.
+    pub fn upgrade_deployed_escrow(
+        e: Env,
+        engagement_id: String,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), ContractError> {
+        ProtocolManager::require_admin(&e)?;
+
+        if Self::get_wasm_hash_version(e.clone(), new_wasm_hash.clone()).is_none() {
+            return Err(ContractError::WasmHashNotApproved);
+        }
+
+        Self::deployed_escrow_client(&e, engagement_id)?.upgrade(&new_wasm_hash);
+
+        Ok(())
+    }
+
+    pub fn migrate_deployed_escrow(
+        e: Env,
+        engagement_id: String,
+        engagement_ids: Vec<String>,
+        usdc_contract: Address,
+    ) -> Result<(), ContractError> {
+        ProtocolManager::require_admin(&e)?;
+
+        Self::deployed_escrow_client(&e, engagement_id)?.migrate(&engagement_ids, &usdc_contract);
+
+        Ok(())
+    }
+
+    fn deployed_escrow_client(e: &Env, engagement_id: String) -> Result<EngagementContractClient<'_>, ContractError> {
+        let deployed_address = Self::get_deployed_escrow(e.clone(), engagement_id)?;
+        Ok(EngagementContractClient::new(e, &deployed_address))
+    }
+
+    /// Adds `wasm_hash` to the approved list, or changes its version if it
+    /// is already listed.
+    pub fn register_wasm_hash(e: Env, wasm_hash: BytesN<32>, version: u32) -> Result<(), ContractError> {
+        let admin = ProtocolManager::require_admin(&e)?;
+
+        let mut approved_wasm_hashes = Self::get_approved_wasm_hashes(e.clone());
+        if let Some(index) = Self::find_wasm_hash(&approved_wasm_hashes, &wasm_hash) {
+            approved_wasm_hashes.remove(index);
+        }
+        approved_wasm_hashes.push_back(WasmHashEntry {
+            wasm_hash: wasm_hash.clone(),
+            version,
+        });
+
+        e.storage().instance().set(&DataKey::ApprovedWasmHashes, &approved_wasm_hashes);
+        extend_instance(&e);
+
+        wasm_hash_registered(&e, admin, wasm_hash, version);
+
+        Ok(())
+    }
+
+    pub fn revoke_wasm_hash(e: Env, wasm_hash: BytesN<32>) -> Result<(), ContractError> {
+        let admin = ProtocolManager::require_admin(&e)?;
+
+        let mut approved_wasm_hashes = Self::get_approved_wasm_hashes(e.clone());
+        let index = Self::find_wasm_hash(&approved_wasm_hashes, &wasm_hash)
+            .ok_or(ContractError::WasmHashNotApproved)?;
+        approved_wasm_hashes.remove(index);
+
+        e.storage().instance().set(&DataKey::ApprovedWasmHashes, &approved_wasm_hashes);
+        extend_instance(&e);
+
+        wasm_hash_revoked(&e, admin, wasm_hash);
+
+        Ok(())
+    }
+
+    pub fn get_approved_wasm_hashes(e: Env) -> Vec<WasmHashEntry> {
+        e.storage()
+            .instance()
+            .get(&DataKey::ApprovedWasmHashes)
+            .unwrap_or(Vec::new(&e))
+    }
+
+    pub fn get_wasm_hash_version(e: Env, wasm_hash: BytesN<32>) -> Option<u32> {
+        let approved_wasm_hashes = Self::get_approved_wasm_hashes(e);
+        Self::find_wasm_hash(&approved_wasm_hashes, &wasm_hash)
+            .and_then(|index| approved_wasm_hashes.get(index))
+            .map(|entry| entry.version)
+    }
+
+    /// The approved hash with the highest version, used by `deploy_escrow`.
+    pub fn get_escrow_wasm_hash(e: Env) -> Result<WasmHashEntry, ContractError> {
+        let mut latest: Option<WasmHashEntry> = None;
+        for entry in Self::get_approved_wasm_hashes(e).iter() {
+            if latest.as_ref().is_none_or(|latest| entry.version > latest.version) {
+                latest = Some(entry);
+            }
+        }
+        latest.ok_or(ContractError::EscrowWasmHashNotSet)
+    }
+
+    fn find_wasm_hash(approved_wasm_hashes: &Vec<WasmHashEntry>, wasm_hash: &BytesN<32>) -> Option<u32> {
+        approved_wasm_hashes
+            .iter()
+            .position(|entry| entry.wasm_hash == *wasm_hash)
+            .map(|index| index as u32)
+    }
+
+    pub fn get_deployed_escrow(e: Env, engagement_id: String) -> Result<Address, ContractError> {
+        read_persistent(&e, &DataKey::DeployedEscrow(engagement_id))
+            .ok_or(ContractError::EscrowNotFound)
+    }
+
+    /// The salt only depends on the engagement id, so the address of an
+    /// escrow instance can be computed before it is deployed.
+    fn escrow_salt(e: &Env, engagement_id: String) -> BytesN<32> {
+        e.crypto().sha256(&engagement_id.to_xdr(e)).into()
+    }
+}
@@ -0,0 +1,126 @@
+use soroban_sdk::{Address, Env, String, Vec};
+use soroban_sdk::token::Client as TokenClient;
+
+use crate::storage::types::{
+    DataKey, Escrow, EscrowMode, LegacyEscrow, Milestone, CURRENT_SCHEMA_VERSION,
+};
+use crate::storage::store::{
+    add_escrow_to_index, extend_instance, index_escrow_participants, write_escrow,
+};
+use crate::error::ContractError;
+use crate::events::escrow_migrated;
+use crate::core::escrow::EscrowManager;
+use crate::core::protocol::ProtocolManager;
+
+pub struct MigrationManager;
+
+impl MigrationManager {
+
+    /// Moves escrows written by the single escrow layout into the current
+    /// one. Escrows that are already in the current layout are skipped, so
+    /// the call can be repeated safely.
+    pub fn migrate(
+        e: Env,
+        engagement_ids: Vec<String>,
+        usdc_contract: Address,
+    ) -> Result<(), ContractError> {
+        ProtocolManager::require_admin(&e)?;
+
+        let mut legacy_escrow_migrated = false;
+        for engagement_id in engagement_ids.iter() {
+            let escrow_key = DataKey::Escrow(engagement_id.clone());
+
+            if e.storage().persistent().has(&escrow_key) {
+                continue;
+            }
+
+            let legacy_escrow: LegacyEscrow = e
+                .storage()
+                .instance()
+                .get(&escrow_key)
+                .ok_or(ContractError::EscrowNotFound)?;
+
+            // The previous layout kept no balance record and allowed a single
+            // escrow per contract. Its funds are what the contract holds, up
+            // to the escrow amount; anything above that is not credited.
+            let contract_balance = TokenClient::new(&e, &usdc_contract)
+                .balance(&e.current_contract_address());
+            let credited_amount = contract_balance.min(legacy_escrow.amount);
+
+            // An escrow with every milestone approved and nothing left in the
+            // contract has already been paid out.
+            let paid_out = credited_amount == 0 && legacy_escrow.milestones.iter().all(|milestone| milestone.flag);
+
+            let escrow = Self::convert_legacy_escrow(&e, legacy_escrow, usdc_contract.clone(), paid_out);
+
+            write_escrow(&e, &escrow);
+            add_escrow_to_index(&e, engagement_id.clone());
+            index_escrow_participants(&e, &escrow);
+
+            if credited_amount > 0 {
+                EscrowManager::record_balance_movement(&e, engagement_id, credited_amount, 0, 0);
+            }
+
+            e.storage().instance().remove(&escrow_key);
+            legacy_escrow_migrated = true;
+
+            escrow_migrated(&e, escrow);
+        }
+
+        // With a single escrow per legacy contract, no legacy entries are left
+        // once it has been migrated.
+        if legacy_escrow_migrated || !e.storage().instance().has(&DataKey::Admin) {
+            e.storage().instance().remove(&DataKey::Admin);
+            e.storage().instance().set(&DataKey::SchemaVersion, &CURRENT_SCHEMA_VERSION);
+        }
+        extend_instance(&e);
+
+        Ok(())
+    }
+
+    fn convert_legacy_escrow(e: &Env, legacy_escrow: LegacyEscrow, asset: Address, paid_out: bool) -> Escrow {
+        // Legacy milestones carry no amount; the escrow amount is split
+        // evenly and the last milestone takes the remainder.
+        let milestone_count = legacy_escrow.milestones.len() as i128;
+        let mut milestones = Vec::<Milestone>::new(e);
+        let mut assigned: i128 = 0;
+
+        for (index, legacy_milestone) in legacy_escrow.milestones.iter().enumerate() {
+            let amount = if index as i128 == milestone_count - 1 {
+                legacy_escrow.amount - assigned
+            } else {
+                legacy_escrow.amount / milestone_count
+            };
+            assigned += amount;
+
+            milestones.push_back(Milestone {
+                description: legacy_milestone.description,
+                status: legacy_milestone.status,
+                amount,
+                flag: legacy_milestone.flag,
+                released: paid_out,
+                dispute_flag: legacy_escrow.dispute_flag,
+            });
+        }
+
+        let mut release_signers = Vec::new(e);
+        release_signers.push_back(legacy_escrow.release_signer);
+
+        Escrow {
+            engagement_id: legacy_escrow.engagement_id,
+            client: legacy_escrow.client,
+            service_provider: legacy_escrow.service_provider,
+            approver: None,
+            platform_address: legacy_escrow.platform_address,
+            asset,
+            amount: legacy_escrow.amount,
+            platform_fee: legacy_escrow.platform_fee,
+            milestones,
+            release_signers,
+            release_threshold: 1,
+            dispute_resolver: legacy_escrow.dispute_resolver,
+            mode: EscrowMode::Milestones,
+            challenge_period: 0,
+        }
+    }
+}
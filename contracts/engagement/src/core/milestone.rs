@@ -1,7 +1,9 @@
 use soroban_sdk::{Address, Env, String, Vec};
 use crate::storage::types::{DataKey, Escrow, Milestone};
+use crate::storage::store::{remove_persistent, write_escrow};
 use crate::error::ContractError;
-use crate::events::escrows_by_engagement_id;
+use crate::events::{milestone_approved, milestone_status_changed};
+use crate::events::types::{MilestoneApprovedEvent, MilestoneStatusChangedEvent};
 use crate::core::escrow::EscrowManager;
 
 pub struct MilestoneManager;
@@ -16,10 +18,6 @@ impl MilestoneManager {
     ) -> Result<(), ContractError> {
         let existing_escrow = EscrowManager::get_escrow_by_id(e.clone(), engagement_id.clone())?;
     
-        if existing_escrow.engagement_id != engagement_id {
-            return Err(ContractError::EscrowNotInitialized);
-        }
-    
         if service_provider != existing_escrow.service_provider {
             return Err(ContractError::OnlyServiceProviderChangeMilstoneStatus);
         }
@@ -47,12 +45,17 @@ impl MilestoneManager {
             ..existing_escrow
         };
     
-        e.storage().instance().set(
-            &DataKey::Escrow(engagement_id.clone().into()),
-            &updated_escrow,
-        );
+        write_escrow(&e, &updated_escrow);
     
-        escrows_by_engagement_id(&e, engagement_id, updated_escrow);
+        milestone_status_changed(
+            &e,
+            engagement_id,
+            service_provider,
+            MilestoneStatusChangedEvent {
+                milestone_index,
+                status: new_status,
+            },
+        );
     
         Ok(())
     }
@@ -62,19 +65,16 @@ impl MilestoneManager {
         engagement_id: String,
         milestone_index: i128,
         new_flag: bool,
-        client: Address,
+        approver: Address,
     ) -> Result<(), ContractError> {
         let existing_escrow = EscrowManager::get_escrow_by_id(e.clone(), engagement_id.clone())?;
     
-        if existing_escrow.engagement_id != engagement_id {
-            return Err(ContractError::EscrowNotInitialized);
-        }
-    
-        if client != existing_escrow.client {
-            return Err(ContractError::OnlyClientChangeMilstoneFlag);
+        let escrow_approver = existing_escrow.approver.clone().unwrap_or(existing_escrow.client.clone());
+        if approver != escrow_approver {
+            return Err(ContractError::OnlyApproverChangeMilstoneFlag);
         }
 
-        client.require_auth();
+        approver.require_auth();
     
         if existing_escrow.milestones.is_empty() {
             return Err(ContractError::NoMileStoneDefined);
@@ -83,6 +83,19 @@ impl MilestoneManager {
         if milestone_index < 0 || milestone_index >= existing_escrow.milestones.len() as i128 {
             return Err(ContractError::InvalidMileStoneIndex);
         }
+
+        let milestone = existing_escrow
+            .milestones
+            .get(milestone_index as u32)
+            .ok_or(ContractError::InvalidMileStoneIndex)?;
+
+        if milestone.dispute_flag {
+            return Err(ContractError::MilestoneOpenedForDisputeResolution);
+        }
+
+        if milestone.released {
+            return Err(ContractError::MilestoneAlreadyReleased);
+        }
     
         let mut updated_milestones = Vec::<Milestone>::new(&e);
         for (index, milestone) in existing_escrow.milestones.iter().enumerate() {
@@ -98,13 +111,20 @@ impl MilestoneManager {
             ..existing_escrow
         };
     
-        e.storage().instance().set(
-            &DataKey::Escrow(engagement_id.clone().into()),
-            &updated_escrow,
+        write_escrow(&e, &updated_escrow);
+        // Release approvals were given for the previous set of approved milestones.
+        remove_persistent(&e, &DataKey::ReleaseApprovals(engagement_id.clone()));
+    
+        milestone_approved(
+            &e,
+            engagement_id,
+            approver,
+            MilestoneApprovedEvent {
+                milestone_index,
+                approved: new_flag,
+            },
         );
     
-        escrows_by_engagement_id(&e, engagement_id, updated_escrow);
-    
         Ok(())
     }
 
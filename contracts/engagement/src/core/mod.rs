@@ -1,10 +1,22 @@
 pub mod user;
-pub mod admin;
+pub mod asset;
 pub mod escrow;
 pub mod dispute;
+pub mod factory;
 pub mod milestone;
+pub mod migration;
+pub mod protocol;
+pub mod retainer;
+pub mod stream;
+pub mod validators;
 
 pub use user::*;
+pub use asset::*;
 pub use escrow::*;
 pub use dispute::*;
+pub use factory::*;
 pub use milestone::*;
+pub use migration::*;
+pub use protocol::*;
+pub use retainer::*;
+pub use stream::*;
@@ -0,0 +1,223 @@
+use soroban_sdk::{Address, BytesN, Env};
+
+use crate::storage::types::{
+    DataKey, ProtocolConfig, CURRENT_SCHEMA_VERSION, LEGACY_SCHEMA_VERSION,
+};
+use crate::storage::store::extend_instance;
+use crate::error::ContractError;
+use crate::events::{
+    admin_changed, admin_transfer_proposed, contract_initialized, contract_paused,
+    contract_unpaused, contract_upgraded, guardian_updated, protocol_config_updated,
+};
+
+pub struct ProtocolManager;
+
+impl ProtocolManager {
+
+    pub fn initialize(
+        e: Env,
+        admin: Address,
+        protocol_config: ProtocolConfig,
+    ) -> Result<(), ContractError> {
+        if e.storage().instance().has(&DataKey::Initialized) {
+            return Err(ContractError::ContractAlreadyInitialized);
+        }
+
+        admin.require_auth();
+
+        Self::validate_protocol_config(&protocol_config)?;
+
+        e.storage().instance().set(&DataKey::Initialized, &true);
+        e.storage().instance().set(&DataKey::ContractAdmin, &admin);
+        e.storage().instance().set(&DataKey::ProtocolConfig, &protocol_config);
+
+        // A contract upgraded from the single escrow layout still holds its
+        // marker under `DataKey::Admin` and keeps the legacy version until
+        // its escrows are migrated.
+        if !e.storage().instance().has(&DataKey::Admin) {
+            e.storage().instance().set(&DataKey::SchemaVersion, &CURRENT_SCHEMA_VERSION);
+        }
+        extend_instance(&e);
+
+        contract_initialized(&e, admin, protocol_config);
+
+        Ok(())
+    }
+
+    /// Hands the contract over in a single call. Both the current and the new
+    /// admin authorize it, so an unreachable address can never become admin.
+    pub fn set_admin(e: Env, new_admin: Address) -> Result<(), ContractError> {
+        let admin = Self::require_admin(&e)?;
+        new_admin.require_auth();
+
+        e.storage().instance().set(&DataKey::ContractAdmin, &new_admin);
+        e.storage().instance().remove(&DataKey::PendingAdmin);
+        extend_instance(&e);
+
+        admin_changed(&e, admin, new_admin);
+
+        Ok(())
+    }
+
+    /// First step of an admin handover. The current admin stays in charge
+    /// until the proposed admin calls `accept_admin`.
+    pub fn transfer_admin(e: Env, new_admin: Address) -> Result<(), ContractError> {
+        let admin = Self::require_admin(&e)?;
+
+        e.storage().instance().set(&DataKey::PendingAdmin, &new_admin);
+        extend_instance(&e);
+
+        admin_transfer_proposed(&e, admin, new_admin);
+
+        Ok(())
+    }
+
+    pub fn accept_admin(e: Env) -> Result<(), ContractError> {
+        let previous_admin = Self::get_admin(e.clone())?;
+        let new_admin: Address = e
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAdmin)
+            .ok_or(ContractError::NoPendingAdmin)?;
+
+        new_admin.require_auth();
+
+        e.storage().instance().set(&DataKey::ContractAdmin, &new_admin);
+        e.storage().instance().remove(&DataKey::PendingAdmin);
+        extend_instance(&e);
+
+        admin_changed(&e, previous_admin, new_admin);
+
+        Ok(())
+    }
+
+    pub fn set_protocol_config(e: Env, protocol_config: ProtocolConfig) -> Result<(), ContractError> {
+        let admin = Self::require_admin(&e)?;
+
+        Self::validate_protocol_config(&protocol_config)?;
+
+        e.storage().instance().set(&DataKey::ProtocolConfig, &protocol_config);
+        extend_instance(&e);
+
+        protocol_config_updated(&e, admin, protocol_config);
+
+        Ok(())
+    }
+
+    pub fn upgrade(e: Env, new_wasm_hash: BytesN<32>) -> Result<(), ContractError> {
+        let admin = Self::require_admin(&e)?;
+
+        e.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+
+        contract_upgraded(&e, admin, new_wasm_hash);
+
+        Ok(())
+    }
+
+    /// Stops funds from moving. The guardian can pause the contract but only
+    /// the admin can resume it.
+    pub fn pause(e: Env, caller: Address) -> Result<(), ContractError> {
+        let admin = Self::get_admin(e.clone())?;
+        if caller != admin && Some(caller.clone()) != Self::get_guardian(e.clone()) {
+            return Err(ContractError::OnlyAdminOrGuardianCanPause);
+        }
+
+        caller.require_auth();
+
+        e.storage().instance().set(&DataKey::Paused, &true);
+        extend_instance(&e);
+
+        contract_paused(&e, caller);
+
+        Ok(())
+    }
+
+    pub fn unpause(e: Env) -> Result<(), ContractError> {
+        let admin = Self::require_admin(&e)?;
+
+        e.storage().instance().remove(&DataKey::Paused);
+        extend_instance(&e);
+
+        contract_unpaused(&e, admin);
+
+        Ok(())
+    }
+
+    pub fn set_guardian(e: Env, guardian: Option<Address>) -> Result<(), ContractError> {
+        let admin = Self::require_admin(&e)?;
+
+        match guardian.clone() {
+            Some(guardian) => e.storage().instance().set(&DataKey::Guardian, &guardian),
+            None => e.storage().instance().remove(&DataKey::Guardian),
+        }
+        extend_instance(&e);
+
+        guardian_updated(&e, admin, guardian);
+
+        Ok(())
+    }
+
+    pub fn is_paused(e: Env) -> bool {
+        e.storage().instance().get(&DataKey::Paused).unwrap_or(false)
+    }
+
+    pub fn get_guardian(e: Env) -> Option<Address> {
+        e.storage().instance().get(&DataKey::Guardian)
+    }
+
+    pub(crate) fn require_not_paused(e: &Env) -> Result<(), ContractError> {
+        if Self::is_paused(e.clone()) {
+            return Err(ContractError::ContractPaused);
+        }
+        Ok(())
+    }
+
+    pub fn get_schema_version(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&DataKey::SchemaVersion)
+            .unwrap_or(LEGACY_SCHEMA_VERSION)
+    }
+
+    pub fn get_admin(e: Env) -> Result<Address, ContractError> {
+        e.storage()
+            .instance()
+            .get(&DataKey::ContractAdmin)
+            .ok_or(ContractError::ContractNotInitialized)
+    }
+
+    pub fn get_pending_admin(e: Env) -> Option<Address> {
+        e.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    pub fn get_protocol_config(e: Env) -> Result<ProtocolConfig, ContractError> {
+        e.storage()
+            .instance()
+            .get(&DataKey::ProtocolConfig)
+            .ok_or(ContractError::ContractNotInitialized)
+    }
+
+    pub(crate) fn require_admin(e: &Env) -> Result<Address, ContractError> {
+        let admin = Self::get_admin(e.clone())?;
+        admin.require_auth();
+        Ok(admin)
+    }
+
+    fn validate_protocol_config(protocol_config: &ProtocolConfig) -> Result<(), ContractError> {
+        if protocol_config.trustless_work_fee < 0 || protocol_config.trustless_work_fee > 10000 {
+            return Err(ContractError::InvalidProtocolConfig);
+        }
+
+        if protocol_config.max_platform_fee < 0 || protocol_config.max_platform_fee > 100 {
+            return Err(ContractError::InvalidProtocolConfig);
+        }
+
+        // The Trustless Work fee is in basis points and the platform fee in
+        // percent; together they can never take more than the whole release.
+        if protocol_config.trustless_work_fee + protocol_config.max_platform_fee * 100 > 10000 {
+            return Err(ContractError::InvalidProtocolConfig);
+        }
+
+        Ok(())
+    }
+}
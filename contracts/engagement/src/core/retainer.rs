@@ -0,0 +1,159 @@
+use soroban_sdk::{Address, Env, String, Vec};
+use soroban_sdk::token::Client as TokenClient;
+
+use crate::storage::types::{DataKey, Escrow, EscrowMode, ReleaseApprovals, RetainerSchedule};
+use crate::storage::store::{read_persistent, remove_persistent, write_escrow, write_persistent};
+use crate::error::ContractError;
+use crate::events::{escrow_cancelled, funds_released, release_approved};
+use crate::events::types::EscrowCancelledEvent;
+use crate::core::escrow::EscrowManager;
+use crate::core::protocol::ProtocolManager;
+
+pub struct RetainerManager;
+
+impl RetainerManager {
+
+    /// Pays the next period of a retainer. Once the period has ended anyone
+    /// can call it, since the funds can only go to the escrow payees. Before
+    /// that, the release signers can release it early: each call records one
+    /// signer's approval and the period is paid when the threshold is reached.
+    pub fn release_retainer_period(
+        e: Env,
+        engagement_id: String,
+        release_signer: Option<Address>,
+    ) -> Result<(), ContractError> {
+        ProtocolManager::require_not_paused(&e)?;
+
+        let escrow = EscrowManager::get_escrow_by_id(e.clone(), engagement_id.clone())?;
+        let mut schedule = Self::retainer_schedule(&escrow)?;
+
+        if schedule.periods_released >= schedule.period_count {
+            return Err(ContractError::NothingToRelease);
+        }
+
+        let approvals_key = DataKey::ReleaseApprovals(engagement_id.clone());
+        let period_due = Self::periods_due(&e, &schedule) > schedule.periods_released;
+        match release_signer {
+            Some(release_signer) => {
+                if !escrow.release_signers.contains(&release_signer) {
+                    return Err(ContractError::OnlyReleaseSignerCanClaimEarnings);
+                }
+                release_signer.require_auth();
+
+                if !period_due {
+                    let mut period_index = Vec::new(&e);
+                    period_index.push_back(schedule.periods_released);
+
+                    let mut approvals = read_persistent::<ReleaseApprovals>(&e, &approvals_key)
+                        .filter(|approvals| approvals.milestone_indexes == period_index)
+                        .unwrap_or(ReleaseApprovals {
+                            milestone_indexes: period_index,
+                            signers: Vec::new(&e),
+                        });
+                    if approvals.signers.contains(&release_signer) {
+                        return Err(ContractError::ReleaseAlreadyApprovedBySigner);
+                    }
+                    approvals.signers.push_back(release_signer.clone());
+
+                    if approvals.signers.len() < escrow.release_threshold {
+                        write_persistent(&e, &approvals_key, &approvals);
+                        release_approved(&e, engagement_id, release_signer, approvals);
+                        return Ok(());
+                    }
+                }
+            }
+            None => {
+                if !period_due {
+                    return Err(ContractError::ReleaseNotYetDue);
+                }
+            }
+        }
+
+        let escrow_balance = EscrowManager::get_escrow_balance(e.clone(), engagement_id.clone())?;
+        if escrow_balance.outstanding < schedule.period_amount {
+            return Err(ContractError::EscrowBalanceNotSufficienteToSendEarnings);
+        }
+
+        let released = EscrowManager::pay_out_earnings(&e, &escrow, schedule.period_amount)?;
+
+        schedule.periods_released += 1;
+        let updated_escrow = Escrow {
+            mode: EscrowMode::Retainer(schedule),
+            ..escrow
+        };
+
+        write_escrow(&e, &updated_escrow);
+        remove_persistent(&e, &approvals_key);
+        EscrowManager::record_balance_movement(&e, engagement_id, 0, released.amount, 0);
+
+        funds_released(&e, &updated_escrow, released);
+
+        Ok(())
+    }
+
+    /// Ends a retainer after the periods that are already due. Those stay
+    /// payable to the service provider and the rest of the funds go back to
+    /// the client.
+    pub fn stop_retainer(e: Env, engagement_id: String) -> Result<(), ContractError> {
+        ProtocolManager::require_not_paused(&e)?;
+
+        let escrow = EscrowManager::get_escrow_by_id(e.clone(), engagement_id.clone())?;
+        let mut schedule = Self::retainer_schedule(&escrow)?;
+
+        escrow.client.require_auth();
+
+        let period_count = schedule.periods_released.max(Self::periods_due(&e, &schedule));
+        if period_count == schedule.period_count {
+            return Err(ContractError::NothingToRelease);
+        }
+
+        let still_owed = (period_count - schedule.periods_released) as i128 * schedule.period_amount;
+        let escrow_balance = EscrowManager::get_escrow_balance(e.clone(), engagement_id.clone())?;
+        let refunded_amount = (escrow_balance.outstanding - still_owed).max(0);
+
+        let asset_client = TokenClient::new(&e, &escrow.asset);
+        EscrowManager::pay_out(&e, &asset_client, &escrow.client, refunded_amount)?;
+
+        schedule.period_count = period_count;
+        let updated_escrow = Escrow {
+            amount: schedule.period_amount * period_count as i128,
+            mode: EscrowMode::Retainer(schedule),
+            ..escrow
+        };
+
+        write_escrow(&e, &updated_escrow);
+        remove_persistent(&e, &DataKey::ReleaseApprovals(engagement_id.clone()));
+        EscrowManager::record_balance_movement(&e, engagement_id, 0, 0, refunded_amount);
+
+        escrow_cancelled(
+            &e,
+            updated_escrow.engagement_id,
+            updated_escrow.client,
+            EscrowCancelledEvent {
+                amount: updated_escrow.amount,
+                refunded_amount,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn retainer_schedule(escrow: &Escrow) -> Result<RetainerSchedule, ContractError> {
+        match &escrow.mode {
+            EscrowMode::Retainer(schedule) => Ok(schedule.clone()),
+            _ => Err(ContractError::NotSupportedForEscrowMode),
+        }
+    }
+
+    /// Number of periods that have ended, each one becoming payable at the
+    /// end of its interval.
+    fn periods_due(e: &Env, schedule: &RetainerSchedule) -> u32 {
+        let now = e.ledger().timestamp();
+        if now < schedule.start_time {
+            return 0;
+        }
+
+        let periods_elapsed = (now - schedule.start_time) / schedule.period_duration;
+        periods_elapsed.min(schedule.period_count as u64) as u32
+    }
+}
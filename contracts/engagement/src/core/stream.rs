@@ -0,0 +1,205 @@
+use soroban_sdk::{Address, Env, String};
+use soroban_sdk::token::Client as TokenClient;
+
+use crate::storage::types::{Escrow, EscrowMode, StreamSchedule};
+use crate::storage::store::write_escrow;
+use crate::error::ContractError;
+use crate::events::{escrow_cancelled, funds_released, stream_dispute_opened, stream_dispute_resolved};
+use crate::events::types::{EscrowCancelledEvent, StreamDisputeResolvedEvent};
+use crate::core::escrow::EscrowManager;
+use crate::core::protocol::ProtocolManager;
+
+pub struct StreamManager;
+
+impl StreamManager {
+
+    /// Pays the service provider everything that has vested and not been
+    /// withdrawn yet, after commissions.
+    pub fn withdraw_vested(e: Env, engagement_id: String) -> Result<(), ContractError> {
+        ProtocolManager::require_not_paused(&e)?;
+
+        let escrow = EscrowManager::get_escrow_by_id(e.clone(), engagement_id.clone())?;
+        let mut schedule = Self::stream_schedule(&escrow)?;
+
+        escrow.service_provider.require_auth();
+
+        // A stream that is not fully funded only pays out what it holds
+        let escrow_balance = EscrowManager::get_escrow_balance(e.clone(), engagement_id.clone())?;
+        let claimable = (Self::vested_amount(&e, escrow.amount, &schedule) - schedule.withdrawn)
+            .min(escrow_balance.outstanding);
+        if claimable <= 0 {
+            return Err(ContractError::NothingToRelease);
+        }
+
+        let released = EscrowManager::pay_out_earnings(&e, &escrow, claimable)?;
+
+        schedule.withdrawn += claimable;
+        let updated_escrow = Escrow {
+            mode: EscrowMode::Stream(schedule),
+            ..escrow
+        };
+
+        write_escrow(&e, &updated_escrow);
+        EscrowManager::record_balance_movement(&e, engagement_id, 0, claimable, 0);
+
+        funds_released(&e, &updated_escrow, released);
+
+        Ok(())
+    }
+
+    /// Stops the stream at the current point. What has vested stays
+    /// withdrawable by the service provider and the rest goes back to the
+    /// client.
+    pub fn halt_stream(e: Env, engagement_id: String) -> Result<(), ContractError> {
+        ProtocolManager::require_not_paused(&e)?;
+
+        let escrow = EscrowManager::get_escrow_by_id(e.clone(), engagement_id)?;
+        let schedule = Self::stream_schedule(&escrow)?;
+
+        escrow.client.require_auth();
+
+        let client = escrow.client.clone();
+        Self::halt(&e, escrow, schedule, client)?;
+
+        Ok(())
+    }
+
+    /// Opened by the client to stop funds from vesting until the dispute
+    /// resolver settles it.
+    pub fn open_stream_dispute(e: Env, engagement_id: String) -> Result<(), ContractError> {
+        let escrow = EscrowManager::get_escrow_by_id(e.clone(), engagement_id)?;
+        let mut schedule = Self::stream_schedule(&escrow)?;
+
+        escrow.client.require_auth();
+
+        if schedule.paused_at.is_some() {
+            return Err(ContractError::EscrowOpenedForDisputeResolution);
+        }
+
+        schedule.paused_at = Some(e.ledger().timestamp());
+        let vested_amount = Self::vested_amount(&e, escrow.amount, &schedule);
+        let updated_escrow = Escrow {
+            mode: EscrowMode::Stream(schedule),
+            ..escrow
+        };
+
+        write_escrow(&e, &updated_escrow);
+
+        stream_dispute_opened(&e, &updated_escrow, vested_amount);
+
+        Ok(())
+    }
+
+    /// Either resumes the stream from where it was paused or halts it at
+    /// the time the dispute was opened.
+    pub fn resolve_stream_dispute(e: Env, engagement_id: String, halt: bool) -> Result<(), ContractError> {
+        ProtocolManager::require_not_paused(&e)?;
+
+        let escrow = EscrowManager::get_escrow_by_id(e.clone(), engagement_id)?;
+        let mut schedule = Self::stream_schedule(&escrow)?;
+
+        escrow.dispute_resolver.require_auth();
+
+        let paused_at = schedule.paused_at.ok_or(ContractError::StreamNotInDispute)?;
+
+        let updated_escrow = if halt {
+            let dispute_resolver = escrow.dispute_resolver.clone();
+            Self::halt(&e, escrow, schedule, dispute_resolver)?
+        } else {
+            schedule.paused_duration += e.ledger().timestamp() - paused_at;
+            schedule.paused_at = None;
+            let updated_escrow = Escrow {
+                mode: EscrowMode::Stream(schedule),
+                ..escrow
+            };
+            write_escrow(&e, &updated_escrow);
+            updated_escrow
+        };
+
+        stream_dispute_resolved(&e, &updated_escrow, StreamDisputeResolvedEvent { halted: halt });
+
+        Ok(())
+    }
+
+    /// Ends the schedule at the point vesting has reached, so the escrow
+    /// amount becomes what has vested, and refunds the client.
+    fn halt(
+        e: &Env,
+        escrow: Escrow,
+        mut schedule: StreamSchedule,
+        cancelled_by: Address,
+    ) -> Result<Escrow, ContractError> {
+        let vested_amount = Self::vested_amount(e, escrow.amount, &schedule);
+        if vested_amount == escrow.amount {
+            return Err(ContractError::NothingToRelease);
+        }
+
+        let still_owed = vested_amount - schedule.withdrawn;
+        let escrow_balance = EscrowManager::get_escrow_balance(e.clone(), escrow.engagement_id.clone())?;
+        let refunded_amount = (escrow_balance.outstanding - still_owed).max(0);
+
+        let asset_client = TokenClient::new(e, &escrow.asset);
+        EscrowManager::pay_out(e, &asset_client, &escrow.client, refunded_amount)?;
+
+        schedule.end_time = schedule.start_time + Self::vesting_elapsed(e, &schedule);
+        schedule.cliff_time = schedule.cliff_time.min(schedule.end_time);
+        schedule.paused_duration = 0;
+        schedule.paused_at = None;
+        let updated_escrow = Escrow {
+            amount: vested_amount,
+            mode: EscrowMode::Stream(schedule),
+            ..escrow
+        };
+
+        write_escrow(e, &updated_escrow);
+        EscrowManager::record_balance_movement(e, updated_escrow.engagement_id.clone(), 0, 0, refunded_amount);
+
+        escrow_cancelled(
+            e,
+            updated_escrow.engagement_id.clone(),
+            cancelled_by,
+            EscrowCancelledEvent {
+                amount: vested_amount,
+                refunded_amount,
+            },
+        );
+
+        Ok(updated_escrow)
+    }
+
+    pub fn get_vested_amount(e: Env, engagement_id: String) -> Result<i128, ContractError> {
+        let escrow = EscrowManager::get_escrow_by_id(e.clone(), engagement_id)?;
+        let schedule = Self::stream_schedule(&escrow)?;
+        Ok(Self::vested_amount(&e, escrow.amount, &schedule))
+    }
+
+    fn stream_schedule(escrow: &Escrow) -> Result<StreamSchedule, ContractError> {
+        match &escrow.mode {
+            EscrowMode::Stream(schedule) => Ok(schedule.clone()),
+            _ => Err(ContractError::NotSupportedForEscrowMode),
+        }
+    }
+
+    /// Time the stream has been vesting for, leaving out time spent in
+    /// disputes.
+    fn vesting_elapsed(e: &Env, schedule: &StreamSchedule) -> u64 {
+        let now = schedule.paused_at.unwrap_or(e.ledger().timestamp());
+        now.saturating_sub(schedule.start_time)
+            .saturating_sub(schedule.paused_duration)
+            .min(schedule.end_time - schedule.start_time)
+    }
+
+    fn vested_amount(e: &Env, amount: i128, schedule: &StreamSchedule) -> i128 {
+        let elapsed = Self::vesting_elapsed(e, schedule);
+        let duration = schedule.end_time - schedule.start_time;
+        if elapsed >= duration {
+            return amount;
+        }
+
+        if elapsed < schedule.cliff_time - schedule.start_time {
+            return 0;
+        }
+
+        amount * elapsed as i128 / duration as i128
+    }
+}
@@ -60,7 +60,7 @@ impl UserManager {
         if let Some(user) = e.storage().persistent().get::<_, User>(&key) {
             user.name
         } else {
-            String::from_str(&e, "User not found")
+            String::from_str(e, "User not found")
         }
     }
     
@@ -0,0 +1,183 @@
+use soroban_sdk::{Env, Vec};
+
+use crate::storage::types::{
+    Escrow, EscrowMode, Milestone, RetainerSchedule, StreamSchedule, MAX_CHALLENGE_PERIOD,
+};
+use crate::error::ContractError;
+use crate::core::protocol::ProtocolManager;
+
+/// Checks shared by `initialize_escrow` and `change_escrow_properties`.
+/// `existing_escrow` is the stored escrow when the properties amend one.
+pub fn validate_escrow_properties(
+    e: &Env,
+    escrow: &Escrow,
+    existing_escrow: Option<&Escrow>,
+) -> Result<(), ContractError> {
+    if escrow.engagement_id.is_empty() {
+        return Err(ContractError::EmptyEngagementId);
+    }
+
+    validate_amount(escrow.amount)?;
+
+    if escrow.client == escrow.service_provider {
+        return Err(ContractError::ClientAndServiceProviderMustDiffer);
+    }
+
+    let protocol_config = ProtocolManager::get_protocol_config(e.clone())?;
+    if escrow.platform_fee < 0 || escrow.platform_fee > protocol_config.max_platform_fee {
+        return Err(ContractError::PlatformFeeTooHigh);
+    }
+
+    match &escrow.mode {
+        EscrowMode::Milestones => {
+            validate_milestones(escrow)?;
+            let stored_milestones = existing_escrow
+                .map(|existing_escrow| existing_escrow.milestones.clone())
+                .unwrap_or(Vec::new(e));
+            validate_milestone_state(escrow, &stored_milestones)?;
+        }
+        EscrowMode::Retainer(schedule) => validate_retainer_schedule(escrow, schedule)?,
+        EscrowMode::Stream(schedule) => validate_stream_schedule(escrow, schedule)?,
+    }
+    validate_release_signers(escrow)?;
+
+    if escrow.challenge_period > MAX_CHALLENGE_PERIOD {
+        return Err(ContractError::InvalidEscrowSchedule);
+    }
+
+    Ok(())
+}
+
+/// Amounts must be positive, for escrows and milestones as for deposits.
+pub fn validate_amount(amount: i128) -> Result<(), ContractError> {
+    if amount < 0 {
+        return Err(ContractError::NegativeAmountNotAllowed);
+    }
+
+    if amount == 0 {
+        return Err(ContractError::AmountCannotBeZero);
+    }
+
+    Ok(())
+}
+
+fn validate_milestones(escrow: &Escrow) -> Result<(), ContractError> {
+    if escrow.milestones.is_empty() {
+        return Err(ContractError::NoMileStoneDefined);
+    }
+
+    let mut milestones_total: i128 = 0;
+    for milestone in escrow.milestones.iter() {
+        validate_amount(milestone.amount)?;
+        milestones_total = milestones_total
+            .checked_add(milestone.amount)
+            .ok_or(ContractError::MilestoneAmountsMustMatchEscrowAmount)?;
+    }
+
+    if milestones_total != escrow.amount {
+        return Err(ContractError::MilestoneAmountsMustMatchEscrowAmount);
+    }
+
+    Ok(())
+}
+
+/// Approval, release and dispute state only changes through the milestone,
+/// release and dispute entry points. Escrow properties must carry the state
+/// stored for each milestone, start new milestones without any, and leave
+/// released or disputed milestones as they are.
+fn validate_milestone_state(escrow: &Escrow, stored_milestones: &Vec<Milestone>) -> Result<(), ContractError> {
+    for (index, stored_milestone) in stored_milestones.iter().enumerate() {
+        if !stored_milestone.released && !stored_milestone.dispute_flag {
+            continue;
+        }
+
+        let unchanged = escrow.milestones.get(index as u32).is_some_and(|milestone| {
+            milestone.description == stored_milestone.description && milestone.amount == stored_milestone.amount
+        });
+        if !unchanged {
+            return Err(milestone_state_error(stored_milestone.released, stored_milestone.dispute_flag));
+        }
+    }
+
+    for (index, milestone) in escrow.milestones.iter().enumerate() {
+        let (flag, released, dispute_flag) = match stored_milestones.get(index as u32) {
+            Some(stored_milestone) => (stored_milestone.flag, stored_milestone.released, stored_milestone.dispute_flag),
+            None => (false, false, false),
+        };
+
+        if milestone.flag != flag || milestone.released != released || milestone.dispute_flag != dispute_flag {
+            return Err(milestone_state_error(
+                milestone.released || released,
+                milestone.dispute_flag || dispute_flag,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn milestone_state_error(released: bool, dispute_flag: bool) -> ContractError {
+    if released {
+        ContractError::MilestoneAlreadyReleased
+    } else if dispute_flag {
+        ContractError::MilestoneOpenedForDisputeResolution
+    } else {
+        ContractError::OnlyApproverChangeMilstoneFlag
+    }
+}
+
+fn validate_retainer_schedule(escrow: &Escrow, schedule: &RetainerSchedule) -> Result<(), ContractError> {
+    if !escrow.milestones.is_empty() {
+        return Err(ContractError::NotSupportedForEscrowMode);
+    }
+
+    validate_amount(schedule.period_amount)?;
+
+    if schedule.period_count == 0
+        || schedule.period_duration == 0
+        || schedule.periods_released > schedule.period_count
+    {
+        return Err(ContractError::InvalidEscrowSchedule);
+    }
+
+    let schedule_total = schedule
+        .period_amount
+        .checked_mul(schedule.period_count as i128)
+        .ok_or(ContractError::InvalidEscrowSchedule)?;
+    if schedule_total != escrow.amount {
+        return Err(ContractError::InvalidEscrowSchedule);
+    }
+
+    Ok(())
+}
+
+fn validate_stream_schedule(escrow: &Escrow, schedule: &StreamSchedule) -> Result<(), ContractError> {
+    if !escrow.milestones.is_empty() {
+        return Err(ContractError::NotSupportedForEscrowMode);
+    }
+
+    if schedule.start_time >= schedule.end_time
+        || schedule.cliff_time < schedule.start_time
+        || schedule.cliff_time > schedule.end_time
+        || schedule.withdrawn < 0
+        || schedule.withdrawn > escrow.amount
+    {
+        return Err(ContractError::InvalidEscrowSchedule);
+    }
+
+    Ok(())
+}
+
+fn validate_release_signers(escrow: &Escrow) -> Result<(), ContractError> {
+    if escrow.release_threshold == 0 || escrow.release_threshold > escrow.release_signers.len() {
+        return Err(ContractError::InvalidReleaseThreshold);
+    }
+
+    for (index, signer) in escrow.release_signers.iter().enumerate() {
+        if escrow.release_signers.first_index_of(&signer) != Some(index as u32) {
+            return Err(ContractError::DuplicateReleaseSigner);
+        }
+    }
+
+    Ok(())
+}
@@ -1,85 +1,120 @@
 use soroban_sdk::contracterror;
 use core::fmt;
 
+/// Errors of the engagement contract.
+///
+/// The failures only the token contract can raise (codes 8, 55, 56, 57 and
+/// 62) live in its `TokenError`. Codes 45, 46 and 54 are in both enums with the
+/// same meaning. A code that is no longer listed stays reserved and is not
+/// reused.
 #[derive(Debug, Copy, Clone, PartialEq)]
 #[contracterror]
 pub enum ContractError {
-    EscrowNotFunded = 1,
     AmountCannotBeZero = 2,
     EscrowAlreadyInitialized = 3,
-    OnlySignerCanFundEscrow = 4,
-    EscrowAlreadyFunded = 5,
     EscrowFullyFunded = 6,
     SignerInsufficientFunds = 7,
-    NotEnoughAllowance = 8,
-    OnlySignerCanCompleteEscrow = 9,
-    EscrowAlreadyCompleted = 10,
-    SignerInsufficientFundsToComplete = 11,
-    OnlyServiceProviderCanCancelEscrow = 12,
-    EscrowAlreadyCancelled = 13,
-    OnlySignerCanRequestRefund = 14,
-    EscrowNotCancelled = 15,
-    NoFundsToRefund = 16,
-    ContractHasInsufficientBalance = 17,
     EscrowNotFound = 18,
     OnlyReleaseSignerCanClaimEarnings = 19,
     EscrowNotCompleted = 20,
     EscrowBalanceNotSufficienteToSendEarnings = 21,
-    ContractInsufficientFunds = 22,
     OnlyPlatformAddressExecuteThisFunction = 23,
-    EscrowNotInitialized = 24,
     OnlyServiceProviderChangeMilstoneStatus = 25,
     NoMileStoneDefined = 26,
     InvalidMileStoneIndex = 27,
-    OnlyClientChangeMilstoneFlag = 28,
+    OnlyApproverChangeMilstoneFlag = 28,
     OnlyDisputeResolverCanExecuteThisFunction = 29,
-    EscrowAlreadyInDispute = 30,
-    EscrowNotInDispute = 31,
+    MilestoneAlreadyInDispute = 30,
+    MilestoneNotInDispute = 31,
     InsufficientFundsForResolution = 32,
-    InvalidState = 33,
     EscrowOpenedForDisputeResolution = 34,
     AmountToDepositGreatherThanEscrowAmount = 35,
-
+    MilestoneOpenedForDisputeResolution = 36,
+    MilestoneAlreadyReleased = 37,
+    ResolutionAmountsMustMatchMilestoneAmount = 38,
+    OnlyClientOrServiceProviderCanOpenDispute = 39,
+    OnlyClientCanChangeApprover = 40,
+    InvalidReleaseThreshold = 41,
+    DuplicateReleaseSigner = 42,
+    ReleaseAlreadyApprovedBySigner = 43,
+    ReleaseSignerNotFound = 44,
+    ContractAlreadyInitialized = 45,
+    ContractNotInitialized = 46,
+    NoPendingAdmin = 48,
+    InvalidProtocolConfig = 49,
+    ContractPaused = 50,
+    OnlyAdminOrGuardianCanPause = 51,
+    EscrowWasmHashNotSet = 52,
+    WasmHashNotApproved = 53,
+    NegativeAmountNotAllowed = 54,
+    EmptyEngagementId = 58,
+    ClientAndServiceProviderMustDiffer = 59,
+    PlatformFeeTooHigh = 60,
+    MilestoneAmountsMustMatchEscrowAmount = 61,
+    AssetNotSupported = 63,
+    AmountBelowAssetMinimum = 64,
+    PayeeCannotReceiveAsset = 65,
+    NotSupportedForEscrowMode = 66,
+    InvalidEscrowSchedule = 67,
+    ReleaseNotYetDue = 68,
+    NothingToRelease = 69,
+    StreamNotInDispute = 70,
+    ReleaseAlreadyRequested = 71,
 }
 
 impl fmt::Display for ContractError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ContractError::EscrowNotFunded => write!(f, "Escrow not funded"),
             ContractError::AmountCannotBeZero => write!(f, "Amount cannot be zero"),
             ContractError::EscrowAlreadyInitialized => write!(f, "Escrow already initialized"),
-            ContractError::OnlySignerCanFundEscrow => write!(f, "Only the signer can fund the escrow"),
-            ContractError::EscrowAlreadyFunded => write!(f, "Escrow already funded"),
             ContractError::EscrowFullyFunded => write!(f, "This escrow is already fully funded"),
             ContractError::SignerInsufficientFunds => write!(f, "The signer does not have sufficient funds"),
-            ContractError::NotEnoughAllowance => write!(f, "Not enough allowance to fund this escrow"),
-            ContractError::OnlySignerCanCompleteEscrow => write!(f, "Only the signer can complete the escrow"),
-            ContractError::EscrowAlreadyCompleted => write!(f, "Escrow already completed"),
-            ContractError::SignerInsufficientFundsToComplete => write!(f, "The signer does not have sufficient funds to complete this escrow"),
-            ContractError::OnlyServiceProviderCanCancelEscrow => write!(f, "Only the service provider can cancel the escrow"),
-            ContractError::EscrowAlreadyCancelled => write!(f, "The escrow has already been cancelled"),
-            ContractError::OnlySignerCanRequestRefund => write!(f, "Only the signer can request a refund"),
-            ContractError::EscrowNotCancelled => write!(f, "The escrow must be cancelled to refund the amounts"),
-            ContractError::NoFundsToRefund => write!(f, "No funds available to refund"),
-            ContractError::ContractHasInsufficientBalance => write!(f, "The contract has no balance to repay"), 
             ContractError::EscrowNotFound => write!(f, "Escrow not found"),
             ContractError::OnlyReleaseSignerCanClaimEarnings => write!(f, "Only the release signer can claim escrow earnings"),
             ContractError::EscrowNotCompleted => write!(f, "The escrow must be completed to claim earnings"),
             ContractError::EscrowBalanceNotSufficienteToSendEarnings => write!(f, "The escrow balance must be equal to the amount of earnings defined for the escrow"),
-            ContractError::ContractInsufficientFunds => write!(f, "The contract does not have sufficient funds"),
             ContractError::OnlyPlatformAddressExecuteThisFunction => write!(f, "Only the plataform address should be able to execute this function"),
-            ContractError::EscrowNotInitialized => write!(f, "Escrow not Initialized"),
             ContractError::OnlyServiceProviderChangeMilstoneStatus => write!(f, "Only ServiceProvider can change MilstoneStatus"),
             ContractError::NoMileStoneDefined => write!(f, "Escrow initialized without Milestone"),
             ContractError::InvalidMileStoneIndex => write!(f, "Invalid Milestone Index"),
-            ContractError::OnlyClientChangeMilstoneFlag => write!(f, "Only Client Can change Milestone Flag"),
+            ContractError::OnlyApproverChangeMilstoneFlag => write!(f, "Only Approver Can change Milestone Flag"),
             ContractError::OnlyDisputeResolverCanExecuteThisFunction => write!(f, "Only Dispute Resolver can execute this function"),
-            ContractError::EscrowAlreadyInDispute => write!(f, "Escrow already in dispute"),
-            ContractError::EscrowNotInDispute => write!(f, "Escrow not in dispute"),
+            ContractError::MilestoneAlreadyInDispute => write!(f, "Milestone already in dispute"),
+            ContractError::MilestoneNotInDispute => write!(f, "Milestone not in dispute"),
             ContractError::InsufficientFundsForResolution => write!(f, "Insufficient funds for resolution"),
-            ContractError::InvalidState => write!(f, "Invalid State"),
             ContractError::EscrowOpenedForDisputeResolution => write!(f, "Escrow has been opened for dispute resolution"),
             ContractError::AmountToDepositGreatherThanEscrowAmount => write!(f, "Amount to deposit is greather thant the escrow amount"),
+            ContractError::MilestoneOpenedForDisputeResolution => write!(f, "Milestone has been opened for dispute resolution"),
+            ContractError::MilestoneAlreadyReleased => write!(f, "Milestone funds have already been released"),
+            ContractError::ResolutionAmountsMustMatchMilestoneAmount => write!(f, "The resolution amounts must add up to the disputed milestone amount"),
+            ContractError::OnlyClientOrServiceProviderCanOpenDispute => write!(f, "Only the client or the service provider can open a dispute"),
+            ContractError::OnlyClientCanChangeApprover => write!(f, "Only the client can change the approver"),
+            ContractError::InvalidReleaseThreshold => write!(f, "The release threshold must be between one and the number of release signers"),
+            ContractError::DuplicateReleaseSigner => write!(f, "A release signer can only be listed once"),
+            ContractError::ReleaseAlreadyApprovedBySigner => write!(f, "This release signer has already approved the release"),
+            ContractError::ReleaseSignerNotFound => write!(f, "The address is not one of the escrow release signers"),
+            ContractError::ContractAlreadyInitialized => write!(f, "The contract has already been initialized"),
+            ContractError::ContractNotInitialized => write!(f, "The contract has not been initialized"),
+            ContractError::NoPendingAdmin => write!(f, "There is no pending admin transfer to accept"),
+            ContractError::InvalidProtocolConfig => write!(f, "Invalid protocol configuration"),
+            ContractError::ContractPaused => write!(f, "The contract is paused"),
+            ContractError::OnlyAdminOrGuardianCanPause => write!(f, "Only the admin or the guardian can pause the contract"),
+            ContractError::EscrowWasmHashNotSet => write!(f, "No escrow wasm hash has been registered"),
+            ContractError::WasmHashNotApproved => write!(f, "The wasm hash is not on the approved list"),
+            ContractError::NegativeAmountNotAllowed => write!(f, "Negative amounts are not allowed"),
+            ContractError::EmptyEngagementId => write!(f, "The engagement id cannot be empty"),
+            ContractError::ClientAndServiceProviderMustDiffer => write!(f, "The client and the service provider must be different addresses"),
+            ContractError::PlatformFeeTooHigh => write!(f, "The platform fee exceeds the maximum allowed by the protocol"),
+            ContractError::MilestoneAmountsMustMatchEscrowAmount => write!(f, "The milestone amounts must add up to the escrow amount"),
+            ContractError::AssetNotSupported => write!(f, "The asset is not on the supported list"),
+            ContractError::AmountBelowAssetMinimum => write!(f, "The escrow amount is below the minimum for its asset"),
+            ContractError::PayeeCannotReceiveAsset => write!(f, "The payee cannot receive the escrow asset"),
+            ContractError::NotSupportedForEscrowMode => write!(f, "This operation is not supported for the escrow mode"),
+            ContractError::InvalidEscrowSchedule => write!(f, "The escrow schedule is invalid or does not add up to the escrow amount"),
+            ContractError::ReleaseNotYetDue => write!(f, "The release is not due yet"),
+            ContractError::NothingToRelease => write!(f, "There are no funds left to release"),
+            ContractError::StreamNotInDispute => write!(f, "The stream is not in dispute"),
+            ContractError::ReleaseAlreadyRequested => write!(f, "A release is already waiting out its challenge period"),
         }
     }
 }
\ No newline at end of file
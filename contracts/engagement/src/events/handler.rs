@@ -1,25 +1,243 @@
-use soroban_sdk::{Env, vec, IntoVal, Val, Address, String, symbol_short};
-use crate::storage::types::Escrow;
+//! Escrow and contract administration events.
+//!
+//! Every event is published with the event name as its first topic. Escrow
+//! events carry the engagement id as their second topic, followed by the
+//! addresses involved in the action. The payload layouts below are part of the
+//! public interface: fields may be added to the end of a payload struct, but
+//! never renamed or removed.
+//!
+//! | Event                      | Topics                                                      | Payload                         |
+//! |----------------------------|-------------------------------------------------------------|---------------------------------|
+//! | `escrow_created`           | `engagement_id`, `client`, `service_provider`               | [`Escrow`]                      |
+//! | `escrow_funded`            | `engagement_id`, `signer`                                   | [`EscrowFundedEvent`]           |
+//! | `milestone_status_changed` | `engagement_id`, `service_provider`                         | [`MilestoneStatusChangedEvent`] |
+//! | `milestone_approved`       | `engagement_id`, `approver`                                 | [`MilestoneApprovedEvent`]      |
+//! | `release_approved`         | `engagement_id`, release signer that approved the release   | [`ReleaseApprovals`]            |
+//! | `release_requested`        | `engagement_id`, release signer that reached the threshold  | [`PendingRelease`]              |
+//! | `funds_released`           | `engagement_id`, `service_provider`, `platform_address`     | [`FundsReleasedEvent`]          |
+//! | `dispute_opened`           | `engagement_id`, `dispute_resolver`                         | [`DisputeOpenedEvent`]          |
+//! | `dispute_resolved`         | `engagement_id`, `client`, `service_provider`               | [`DisputeResolvedEvent`]        |
+//! | `escrow_amended`           | `engagement_id`, address that authorized the change         | [`Escrow`] after the change     |
+//! | `escrow_migrated`          | `engagement_id`                                             | [`Escrow`] in the current layout|
+//! | `escrow_deployed`          | `engagement_id`, `platform_address`                         | deployed escrow address         |
+//! | `escrow_cancelled`         | `engagement_id`, address that cancelled the escrow          | [`EscrowCancelledEvent`]        |
+//! | `stream_dispute_opened`    | `engagement_id`, `dispute_resolver`                         | amount vested when paused       |
+//! | `stream_dispute_resolved`  | `engagement_id`, `dispute_resolver`                         | [`StreamDisputeResolvedEvent`]  |
+//!
+//! | Event                      | Topics                                                      | Payload                         |
+//! |----------------------------|-------------------------------------------------------------|---------------------------------|
+//! | `contract_initialized`     | `admin`                                                     | [`ProtocolConfig`]              |
+//! | `admin_transfer_proposed`  | `admin`, `pending_admin`                                    | `()`                            |
+//! | `admin_changed`            | `previous_admin`, `new_admin`                               | `()`                            |
+//! | `protocol_config_updated`  | `admin`                                                     | [`ProtocolConfig`]              |
+//! | `contract_upgraded`        | `admin`                                                     | new wasm hash                   |
+//! | `contract_paused`          | address that paused the contract                            | `()`                            |
+//! | `contract_unpaused`        | `admin`                                                     | `()`                            |
+//! | `guardian_updated`         | `admin`                                                     | new guardian, if any            |
+//! | `wasm_hash_registered`     | `admin`, `wasm_hash`                                        | version                         |
+//! | `wasm_hash_revoked`        | `admin`, `wasm_hash`                                        | `()`                            |
+//! | `asset_supported`          | `admin`, `asset`                                            | minimum escrow amount           |
+//! | `asset_removed`            | `admin`, `asset`                                            | `()`                            |
+
+use soroban_sdk::{Address, BytesN, Env, String, Symbol};
+use crate::storage::types::{Escrow, PendingRelease, ProtocolConfig, ReleaseApprovals};
+use crate::events::types::{
+    DisputeOpenedEvent,
+    DisputeResolvedEvent,
+    EscrowCancelledEvent,
+    EscrowFundedEvent,
+    FundsReleasedEvent,
+    MilestoneApprovedEvent,
+    MilestoneStatusChangedEvent,
+    StreamDisputeResolvedEvent,
+};
 
 // ------ Escrows
-pub fn escrows_by_engagement_id(e: &Env, engagement_id: String, escrow: Escrow) {
-    let topics = (symbol_short!("p_by_spdr"),);
-    
-    let engagement_id_val: Val = engagement_id.into_val(e);
-    let escrow_val: Val = escrow.into_val(e);
 
-    let event_payload = vec![e, engagement_id_val, escrow_val];
-    e.events().publish(topics, event_payload);
+pub fn escrow_created(e: &Env, escrow: Escrow) {
+    let topics = (
+        Symbol::new(e, "escrow_created"),
+        escrow.engagement_id.clone(),
+        escrow.client.clone(),
+        escrow.service_provider.clone(),
+    );
+    e.events().publish(topics, escrow);
+}
+
+pub fn escrow_funded(e: &Env, engagement_id: String, signer: Address, payload: EscrowFundedEvent) {
+    let topics = (Symbol::new(e, "escrow_funded"), engagement_id, signer);
+    e.events().publish(topics, payload);
+}
+
+pub fn funds_released(e: &Env, escrow: &Escrow, payload: FundsReleasedEvent) {
+    let topics = (
+        Symbol::new(e, "funds_released"),
+        escrow.engagement_id.clone(),
+        escrow.service_provider.clone(),
+        escrow.platform_address.clone(),
+    );
+    e.events().publish(topics, payload);
+}
+
+pub fn release_approved(e: &Env, engagement_id: String, release_signer: Address, approvals: ReleaseApprovals) {
+    let topics = (Symbol::new(e, "release_approved"), engagement_id, release_signer);
+    e.events().publish(topics, approvals);
 }
 
-// ------ Token
+pub fn release_requested(e: &Env, engagement_id: String, release_signer: Address, pending_release: PendingRelease) {
+    let topics = (Symbol::new(e, "release_requested"), engagement_id, release_signer);
+    e.events().publish(topics, pending_release);
+}
+
+pub fn escrow_amended(e: &Env, authorized_by: Address, escrow: Escrow) {
+    let topics = (
+        Symbol::new(e, "escrow_amended"),
+        escrow.engagement_id.clone(),
+        authorized_by,
+    );
+    e.events().publish(topics, escrow);
+}
+
+pub fn escrow_migrated(e: &Env, escrow: Escrow) {
+    let topics = (Symbol::new(e, "escrow_migrated"), escrow.engagement_id.clone());
+    e.events().publish(topics, escrow);
+}
+
+pub fn escrow_deployed(
+    e: &Env,
+    engagement_id: String,
+    platform_address: Address,
+    deployed_address: Address,
+) {
+    let topics = (Symbol::new(e, "escrow_deployed"), engagement_id, platform_address);
+    e.events().publish(topics, deployed_address);
+}
 
-pub fn balance_retrieved_event(e: &Env, address: Address, usdc_token_address: Address, balance: i128) {
-    let topics = (symbol_short!("blnc"),);
-    let address_val: Val = address.into_val(e);
-    let token_address_val: Val = usdc_token_address.into_val(e);
-    let balance_val: Val = balance.into_val(e);
+pub fn escrow_cancelled(e: &Env, engagement_id: String, cancelled_by: Address, payload: EscrowCancelledEvent) {
+    let topics = (Symbol::new(e, "escrow_cancelled"), engagement_id, cancelled_by);
+    e.events().publish(topics, payload);
+}
+
+pub fn stream_dispute_opened(e: &Env, escrow: &Escrow, vested_amount: i128) {
+    let topics = (
+        Symbol::new(e, "stream_dispute_opened"),
+        escrow.engagement_id.clone(),
+        escrow.dispute_resolver.clone(),
+    );
+    e.events().publish(topics, vested_amount);
+}
+
+pub fn stream_dispute_resolved(e: &Env, escrow: &Escrow, payload: StreamDisputeResolvedEvent) {
+    let topics = (
+        Symbol::new(e, "stream_dispute_resolved"),
+        escrow.engagement_id.clone(),
+        escrow.dispute_resolver.clone(),
+    );
+    e.events().publish(topics, payload);
+}
+
+// ------ Milestones
+
+pub fn milestone_status_changed(
+    e: &Env,
+    engagement_id: String,
+    service_provider: Address,
+    payload: MilestoneStatusChangedEvent,
+) {
+    let topics = (Symbol::new(e, "milestone_status_changed"), engagement_id, service_provider);
+    e.events().publish(topics, payload);
+}
+
+pub fn milestone_approved(
+    e: &Env,
+    engagement_id: String,
+    approver: Address,
+    payload: MilestoneApprovedEvent,
+) {
+    let topics = (Symbol::new(e, "milestone_approved"), engagement_id, approver);
+    e.events().publish(topics, payload);
+}
+
+// ------ Disputes
+
+pub fn dispute_opened(
+    e: &Env,
+    engagement_id: String,
+    dispute_resolver: Address,
+    payload: DisputeOpenedEvent,
+) {
+    let topics = (Symbol::new(e, "dispute_opened"), engagement_id, dispute_resolver);
+    e.events().publish(topics, payload);
+}
 
-    let event_payload = vec![e, address_val, token_address_val, balance_val];
-    e.events().publish(topics, event_payload);
-}
\ No newline at end of file
+pub fn dispute_resolved(e: &Env, escrow: &Escrow, payload: DisputeResolvedEvent) {
+    let topics = (
+        Symbol::new(e, "dispute_resolved"),
+        escrow.engagement_id.clone(),
+        escrow.client.clone(),
+        escrow.service_provider.clone(),
+    );
+    e.events().publish(topics, payload);
+}
+
+// ------ Administration
+
+pub fn contract_initialized(e: &Env, admin: Address, protocol_config: ProtocolConfig) {
+    let topics = (Symbol::new(e, "contract_initialized"), admin);
+    e.events().publish(topics, protocol_config);
+}
+
+pub fn admin_transfer_proposed(e: &Env, admin: Address, pending_admin: Address) {
+    let topics = (Symbol::new(e, "admin_transfer_proposed"), admin, pending_admin);
+    e.events().publish(topics, ());
+}
+
+pub fn admin_changed(e: &Env, previous_admin: Address, new_admin: Address) {
+    let topics = (Symbol::new(e, "admin_changed"), previous_admin, new_admin);
+    e.events().publish(topics, ());
+}
+
+pub fn protocol_config_updated(e: &Env, admin: Address, protocol_config: ProtocolConfig) {
+    let topics = (Symbol::new(e, "protocol_config_updated"), admin);
+    e.events().publish(topics, protocol_config);
+}
+
+pub fn contract_upgraded(e: &Env, admin: Address, new_wasm_hash: BytesN<32>) {
+    let topics = (Symbol::new(e, "contract_upgraded"), admin);
+    e.events().publish(topics, new_wasm_hash);
+}
+
+pub fn contract_paused(e: &Env, paused_by: Address) {
+    let topics = (Symbol::new(e, "contract_paused"), paused_by);
+    e.events().publish(topics, ());
+}
+
+pub fn contract_unpaused(e: &Env, admin: Address) {
+    let topics = (Symbol::new(e, "contract_unpaused"), admin);
+    e.events().publish(topics, ());
+}
+
+pub fn guardian_updated(e: &Env, admin: Address, guardian: Option<Address>) {
+    let topics = (Symbol::new(e, "guardian_updated"), admin);
+    e.events().publish(topics, guardian);
+}
+
+pub fn wasm_hash_registered(e: &Env, admin: Address, wasm_hash: BytesN<32>, version: u32) {
+    let topics = (Symbol::new(e, "wasm_hash_registered"), admin, wasm_hash);
+    e.events().publish(topics, version);
+}
+
+pub fn wasm_hash_revoked(e: &Env, admin: Address, wasm_hash: BytesN<32>) {
+    let topics = (Symbol::new(e, "wasm_hash_revoked"), admin, wasm_hash);
+    e.events().publish(topics, ());
+}
+
+pub fn asset_supported(e: &Env, admin: Address, asset: Address, minimum_amount: i128) {
+    let topics = (Symbol::new(e, "asset_supported"), admin, asset);
+    e.events().publish(topics, minimum_amount);
+}
+
+pub fn asset_removed(e: &Env, admin: Address, asset: Address) {
+    let topics = (Symbol::new(e, "asset_removed"), admin, asset);
+    e.events().publish(topics, ());
+}
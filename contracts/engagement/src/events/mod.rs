@@ -1,6 +1,32 @@
 pub mod handler;
+pub mod types;
 
 pub (crate) use handler::{
-    escrows_by_engagement_id,
-    balance_retrieved_event,
-};
\ No newline at end of file
+    escrow_created,
+    escrow_funded,
+    release_approved,
+    release_requested,
+    funds_released,
+    escrow_amended,
+    escrow_cancelled,
+    escrow_migrated,
+    escrow_deployed,
+    stream_dispute_opened,
+    stream_dispute_resolved,
+    milestone_status_changed,
+    milestone_approved,
+    dispute_opened,
+    dispute_resolved,
+    contract_initialized,
+    admin_transfer_proposed,
+    admin_changed,
+    protocol_config_updated,
+    contract_upgraded,
+    contract_paused,
+    contract_unpaused,
+    guardian_updated,
+    wasm_hash_registered,
+    wasm_hash_revoked,
+    asset_supported,
+    asset_removed,
+};
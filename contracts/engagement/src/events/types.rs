@@ -0,0 +1,60 @@
+use soroban_sdk::{contracttype, String};
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EscrowFundedEvent {
+    pub amount: i128,
+    pub total_funded: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MilestoneStatusChangedEvent {
+    pub milestone_index: i128,
+    pub status: String,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MilestoneApprovedEvent {
+    pub milestone_index: i128,
+    pub approved: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FundsReleasedEvent {
+    pub amount: i128,
+    pub trustless_work_commission: i128,
+    pub platform_commission: i128,
+    pub service_provider_amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DisputeOpenedEvent {
+    pub milestone_index: i128,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DisputeResolvedEvent {
+    pub milestone_index: i128,
+    pub client_funds: i128,
+    pub service_provider_funds: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EscrowCancelledEvent {
+    // Escrow amount left for the payees once the rest is refunded
+    pub amount: i128,
+    pub refunded_amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StreamDisputeResolvedEvent {
+    pub halted: bool,
+}
@@ -2,7 +2,6 @@
 
 mod core;
 mod storage;
-mod token;
 mod events;
 mod error;
 mod contract;
@@ -1,31 +1,193 @@
-use soroban_sdk::contracttype;
+use soroban_sdk::{Address, Env, IntoVal, String, TryFromVal, Val, Vec};
 
-#[derive(Clone)]
-#[contracttype]
+use crate::storage::types::{
+    DataKey, Escrow, Role, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD, MAX_ESCROWS_SCANNED,
+    PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD,
+};
 
-enum DataKeyAddress {
-    Initialized,
-    TotalAddress,
-    Shares(u32),
-    Addresses(u32),
+pub fn extend_instance(e: &Env) {
+    e.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
 }
 
-// pub fn get_all_engagements(e: Env) -> Vec<Escrow> {
-//     let engagement_count: u128 = e
-//         .storage()
-//         .instance()
-//         .get(&symbol_short!("pk"))
-//         .unwrap_or(0);
+pub fn extend_persistent(e: &Env, key: &DataKey) {
+    if e.storage().persistent().has(key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+}
+
+pub fn read_persistent<V: TryFromVal<Env, Val>>(e: &Env, key: &DataKey) -> Option<V> {
+    let value = e.storage().persistent().get(key);
+    if value.is_some() {
+        extend_persistent(e, key);
+    }
+    value
+}
+
+pub fn write_persistent<V: IntoVal<Env, Val>>(e: &Env, key: &DataKey, value: &V) {
+    e.storage().persistent().set(key, value);
+    extend_persistent(e, key);
+}
+
+pub fn remove_persistent(e: &Env, key: &DataKey) {
+    e.storage().persistent().remove(key);
+}
+
+pub fn read_escrow(e: &Env, engagement_id: String) -> Option<Escrow> {
+    read_persistent(e, &DataKey::Escrow(engagement_id))
+}
+
+/// Escrows rely on the instance for the code, the protocol config and the
+/// pause flag, so it is extended whenever one of them changes.
+pub fn write_escrow(e: &Env, escrow: &Escrow) {
+    write_persistent(e, &DataKey::Escrow(escrow.engagement_id.clone()), escrow);
+    extend_instance(e);
+}
+
+/// Extends every persistent entry that belongs to an escrow, its index
+/// entries included, so that long running engagements are not archived
+/// between interactions.
+pub fn bump_escrow_entries(e: &Env, escrow: &Escrow) {
+    let engagement_id = escrow.engagement_id.clone();
+    extend_persistent(e, &DataKey::Escrow(engagement_id.clone()));
+    extend_persistent(e, &DataKey::ReleaseApprovals(engagement_id.clone()));
+    extend_persistent(e, &DataKey::PendingRelease(engagement_id.clone()));
+    extend_persistent(e, &DataKey::EscrowBalance(engagement_id.clone()));
+    extend_persistent(e, &DataKey::DeployedEscrow(engagement_id.clone()));
+
+    let index_position_key = DataKey::EscrowIndexPosition(engagement_id.clone());
+    if let Some(index) = read_persistent::<u32>(e, &index_position_key) {
+        extend_persistent(e, &DataKey::EscrowIndex(index));
+    }
+
+    for (role, address) in escrow_participants(e, escrow).iter() {
+        let position_key = DataKey::EscrowRolePosition(role.clone(), address.clone(), engagement_id.clone());
+        if let Some(position) = read_persistent::<u32>(e, &position_key) {
+            extend_persistent(e, &DataKey::EscrowsByRole(role.clone(), address.clone(), position));
+            extend_persistent(e, &DataKey::EscrowsByRoleCount(role, address));
+        }
+    }
+
+    extend_instance(e);
+}
+
+fn read_escrows_by_role_count(e: &Env, role: Role, address: Address) -> u32 {
+    read_persistent(e, &DataKey::EscrowsByRoleCount(role, address)).unwrap_or(0)
+}
 
-//     let mut engagements: Vec<Engagement> = Vec::new(&e);
+/// Reads one page of the role index, of at most `MAX_ESCROWS_SCANNED` ids.
+/// Each escrow of an address is stored under its own position so that the
+/// index never grows into a single entry.
+pub fn read_escrows_by_role(e: &Env, role: Role, address: Address, start: u32, limit: u32) -> Vec<String> {
+    let count = read_escrows_by_role_count(e, role.clone(), address.clone());
+    let end = start.saturating_add(limit.min(MAX_ESCROWS_SCANNED)).min(count);
+
+    let mut engagement_ids = Vec::new(e);
+    for position in start..end {
+        let key = DataKey::EscrowsByRole(role.clone(), address.clone(), position);
+        if let Some(engagement_id) = read_persistent(e, &key) {
+            engagement_ids.push_back(engagement_id);
+        }
+    }
+    engagement_ids
+}
 
-//     for id in 1..=engagement_count {
-//         let engagement_id_in_bytes = u128_to_bytes(&e, id);
-//         let engagement_key = DataKey::Engagement(engagement_id_in_bytes);
-//         if let Some(engagement) = e.storage().instance().get(&engagement_key) {
-//             engagements.push_back(engagement);
-//         }
-//     }
+fn add_escrow_to_role(e: &Env, role: Role, address: Address, engagement_id: String) {
+    let position_key = DataKey::EscrowRolePosition(role.clone(), address.clone(), engagement_id.clone());
+    if e.storage().persistent().has(&position_key) {
+        return;
+    }
 
-//     engagements
-// }
\ No newline at end of file
+    let count = read_escrows_by_role_count(e, role.clone(), address.clone());
+    write_persistent(e, &DataKey::EscrowsByRole(role.clone(), address.clone(), count), &engagement_id);
+    write_persistent(e, &position_key, &count);
+    write_persistent(e, &DataKey::EscrowsByRoleCount(role, address), &(count + 1));
+}
+
+fn remove_escrow_from_role(e: &Env, role: Role, address: Address, engagement_id: String) {
+    let position_key = DataKey::EscrowRolePosition(role.clone(), address.clone(), engagement_id);
+    let position: u32 = match read_persistent(e, &position_key) {
+        Some(position) => position,
+        None => return,
+    };
+
+    // The last entry moves into the freed position so positions stay contiguous.
+    let last = read_escrows_by_role_count(e, role.clone(), address.clone()) - 1;
+    let last_key = DataKey::EscrowsByRole(role.clone(), address.clone(), last);
+    if position != last {
+        if let Some(last_engagement_id) = read_persistent::<String>(e, &last_key) {
+            write_persistent(e, &DataKey::EscrowsByRole(role.clone(), address.clone(), position), &last_engagement_id);
+            write_persistent(e, &DataKey::EscrowRolePosition(role.clone(), address.clone(), last_engagement_id), &position);
+        }
+    }
+
+    remove_persistent(e, &last_key);
+    remove_persistent(e, &position_key);
+    let count_key = DataKey::EscrowsByRoleCount(role, address);
+    if last == 0 {
+        remove_persistent(e, &count_key);
+    } else {
+        write_persistent(e, &count_key, &last);
+    }
+}
+
+fn escrow_participants(e: &Env, escrow: &Escrow) -> Vec<(Role, Address)> {
+    let mut participants = Vec::new(e);
+    participants.push_back((Role::Client, escrow.client.clone()));
+    participants.push_back((Role::ServiceProvider, escrow.service_provider.clone()));
+    participants.push_back((Role::Platform, escrow.platform_address.clone()));
+    participants.push_back((
+        Role::Approver,
+        escrow.approver.clone().unwrap_or(escrow.client.clone()),
+    ));
+    for release_signer in escrow.release_signers.iter() {
+        participants.push_back((Role::ReleaseSigner, release_signer));
+    }
+    participants.push_back((Role::DisputeResolver, escrow.dispute_resolver.clone()));
+    participants
+}
+
+pub fn index_escrow_participants(e: &Env, escrow: &Escrow) {
+    for (role, address) in escrow_participants(e, escrow).iter() {
+        add_escrow_to_role(e, role, address, escrow.engagement_id.clone());
+    }
+}
+
+/// Updates the role index after a change of participants. Only the roles
+/// whose address changed are touched, so the escrow keeps its position in
+/// the pages of everyone else.
+pub fn reindex_escrow_participants(e: &Env, existing_escrow: &Escrow, updated_escrow: &Escrow) {
+    let existing_participants = escrow_participants(e, existing_escrow);
+    let updated_participants = escrow_participants(e, updated_escrow);
+
+    for (role, address) in existing_participants.iter() {
+        if !updated_participants.contains((role.clone(), address.clone())) {
+            remove_escrow_from_role(e, role, address, existing_escrow.engagement_id.clone());
+        }
+    }
+
+    for (role, address) in updated_participants.iter() {
+        if !existing_participants.contains((role.clone(), address.clone())) {
+            add_escrow_to_role(e, role, address, updated_escrow.engagement_id.clone());
+        }
+    }
+}
+
+pub fn read_escrow_count(e: &Env) -> u32 {
+    e.storage().instance().get(&DataKey::EscrowCounter).unwrap_or(0)
+}
+
+pub fn read_escrow_id_at(e: &Env, index: u32) -> Option<String> {
+    read_persistent(e, &DataKey::EscrowIndex(index))
+}
+
+pub fn add_escrow_to_index(e: &Env, engagement_id: String) {
+    let index = read_escrow_count(e);
+    write_persistent(e, &DataKey::EscrowIndex(index), &engagement_id);
+    write_persistent(e, &DataKey::EscrowIndexPosition(engagement_id), &index);
+    e.storage().instance().set(&DataKey::EscrowCounter, &(index + 1));
+    extend_instance(e);
+}
@@ -1,8 +1,20 @@
-use soroban_sdk::{contracttype, Address, String, Vec};
+use soroban_sdk::{contracttype, Address, BytesN, String, Vec};
 
 pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
 pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
 pub(crate) const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+pub(crate) const PERSISTENT_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+// Version 1 is the single escrow layout kept in instance storage
+pub(crate) const LEGACY_SCHEMA_VERSION: u32 = 1;
+pub(crate) const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+// Escrows a role lookup returns, and list_escrows looks at, in a single call
+pub(crate) const MAX_ESCROWS_SCANNED: u32 = 50;
+
+// Longest challenge period, in seconds, a release can be held back for
+pub(crate) const MAX_CHALLENGE_PERIOD: u64 = 30 * 24 * 60 * 60;
 
 #[contracttype]
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -10,13 +22,58 @@ pub struct Escrow {
     pub engagement_id: String,
     pub client: Address,
     pub service_provider: Address,
+    // When empty, milestones are approved by the client
+    pub approver: Option<Address>,
     pub platform_address: Address,
+    // Token contract the escrow is funded and paid out in
+    pub asset: Address,
     pub amount: i128,
     pub platform_fee: i128,
     pub milestones: Vec<Milestone>,
-    pub release_signer: Address,
+    pub release_signers: Vec<Address>,
+    pub release_threshold: u32,
     pub dispute_resolver: Address,
-    pub dispute_flag: bool,
+    pub mode: EscrowMode,
+    // Seconds a requested milestone release waits before it can be executed,
+    // so the client can still dispute it; zero releases immediately
+    pub challenge_period: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EscrowMode {
+    // Funds are released as milestones are approved
+    Milestones,
+    // Funds are released one period at a time and the escrow has no milestones
+    Retainer(RetainerSchedule),
+    // Funds vest linearly to the service provider and the escrow has no milestones
+    Stream(StreamSchedule),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetainerSchedule {
+    pub period_amount: i128,
+    pub period_count: u32,
+    // Length of a period in seconds
+    pub period_duration: u64,
+    // Ledger timestamp the first period starts at
+    pub start_time: u64,
+    pub periods_released: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StreamSchedule {
+    // Ledger timestamps; nothing vests before the cliff and everything by the end
+    pub start_time: u64,
+    pub cliff_time: u64,
+    pub end_time: u64,
+    pub withdrawn: i128,
+    // Set while a dispute is open; accrual stops at this time
+    pub paused_at: Option<u64>,
+    // Time spent in disputes, which pushes the rest of the schedule back
+    pub paused_duration: u64,
 }
 
 #[contracttype]
@@ -24,21 +81,112 @@ pub struct Escrow {
 pub struct Milestone {
     pub description: String,
     pub status: String,
+    pub amount: i128,
     pub flag: bool,
+    pub released: bool,
+    pub dispute_flag: bool,
 }
 
 #[contracttype]
-#[derive(Clone)]
-pub struct AllowanceValue {
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LegacyEscrow {
+    pub engagement_id: String,
+    pub client: Address,
+    pub service_provider: Address,
+    pub platform_address: Address,
     pub amount: i128,
-    pub expiration_ledger: u32,
+    pub platform_fee: i128,
+    pub milestones: Vec<LegacyMilestone>,
+    pub release_signer: Address,
+    pub dispute_resolver: Address,
+    pub dispute_flag: bool,
 }
 
 #[contracttype]
-#[derive(Clone)]
-pub struct AllowanceDataKey {
-    pub from: Address,
-    pub spender: Address,
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LegacyMilestone {
+    pub description: String,
+    pub status: String,
+    pub flag: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EscrowBalance {
+    pub funded: i128,
+    pub released: i128,
+    pub refunded: i128,
+    pub outstanding: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProtocolConfig {
+    pub trustless_work_address: Address,
+    // Commission charged on every release, in basis points
+    pub trustless_work_fee: i128,
+    // Highest platform fee an escrow may set, as a percentage
+    pub max_platform_fee: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WasmHashEntry {
+    pub wasm_hash: BytesN<32>,
+    pub version: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SupportedAsset {
+    pub asset: Address,
+    // Smallest escrow amount accepted in this asset, in its own units
+    pub minimum_amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EscrowStatus {
+    Initialized,
+    Funded,
+    InDispute,
+    Completed,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EscrowPage {
+    pub escrows: Vec<Escrow>,
+    // Position to continue listing from, if any escrows are left
+    pub next_start: Option<u32>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReleaseApprovals {
+    // Milestones, or the retainer period, the release signers approved releasing
+    pub milestone_indexes: Vec<u32>,
+    pub signers: Vec<Address>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingRelease {
+    // Milestones approved for release when the request was made
+    pub milestone_indexes: Vec<u32>,
+    // Ledger timestamp the release can be executed from
+    pub executable_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Role {
+    Client,
+    ServiceProvider,
+    Platform,
+    Approver,
+    ReleaseSigner,
+    DisputeResolver,
 }
 
 #[derive(Clone)]
@@ -56,10 +204,32 @@ pub struct User {
 #[contracttype]
 pub enum DataKey {
     Escrow(String),
-    Balance(Address),
-    Allowance(AllowanceDataKey),
+    ReleaseApprovals(String),
+    PendingRelease(String),
+    EscrowBalance(String),
+    EscrowsByRole(Role, Address, u32),
+    EscrowsByRoleCount(Role, Address),
+    EscrowRolePosition(Role, Address, String),
+    EscrowCounter,
+    EscrowIndex(u32),
+    EscrowIndexPosition(String),
+    // Set by version 1 contracts, marks a legacy escrow awaiting migration
     Admin,
 
+    // Engagement contract administration
+    Initialized,
+    ContractAdmin,
+    PendingAdmin,
+    ProtocolConfig,
+    SchemaVersion,
+    Paused,
+    Guardian,
+    SupportedAssets,
+
+    // Escrow factory
+    ApprovedWasmHashes,
+    DeployedEscrow(String),
+
     // User storage
     User(Address),
     UserRegId(Address),
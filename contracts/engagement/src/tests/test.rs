@@ -2,11 +2,35 @@
 
 extern crate std;
 
-use crate::storage::types::Milestone;
-use crate::token::token::{Token, TokenClient};
+use crate::storage::types::{
+    DataKey, Escrow, EscrowMode, EscrowStatus, LegacyEscrow, LegacyMilestone, Milestone, PendingRelease,
+    ProtocolConfig, ReleaseApprovals, RetainerSchedule, Role, StreamSchedule, SupportedAsset, WasmHashEntry, DAY_IN_LEDGERS,
+    INSTANCE_BUMP_AMOUNT, MAX_CHALLENGE_PERIOD, MAX_ESCROWS_SCANNED, PERSISTENT_BUMP_AMOUNT,
+};
+use crate::error::ContractError;
+use crate::events::types::{
+    DisputeOpenedEvent, EscrowCancelledEvent, FundsReleasedEvent, MilestoneStatusChangedEvent,
+    StreamDisputeResolvedEvent,
+};
+use token::{Token, TokenClient, TokenError};
 use crate::contract::EngagementContract;
 use crate::contract::EngagementContractClient;
-use soroban_sdk::{testutils::Address as _, vec, Address, Env, IntoVal, String};
+use soroban_sdk::{
+    testutils::{
+        storage::{Instance as _, Persistent as _}, Address as _, AuthorizedFunction, AuthorizedInvocation,
+        EnvTestConfig, Events, IssuerFlags, Ledger, MockAuth, MockAuthInvoke,
+    },
+    token::StellarAssetClient,
+    xdr::ToXdr,
+    contract, contractimpl, vec, Address, BytesN, Env, IntoVal, String, Symbol,
+};
+
+// The engagement contract compiled to wasm, so that the factory can deploy it.
+// Rebuilt with `cargo build -p engagement --target wasm32v1-none --release`
+// whenever the contract changes.
+mod escrow_wasm {
+    soroban_sdk::contractimport!(file = "src/tests/fixtures/engagement.wasm");
+}
 
 fn create_usdc_token<'a>(e: &Env, admin: &Address) -> TokenClient<'a> {
     let token = TokenClient::new(e, &e.register_contract(None, Token {}));
@@ -14,6 +38,770 @@ fn create_usdc_token<'a>(e: &Env, admin: &Address) -> TokenClient<'a> {
     token
 }
 
+/// Minimal token that burns 1% of every transfer, to check that escrows are
+/// credited with what they receive rather than what was sent.
+#[contract]
+struct FeeOnTransferToken;
+
+#[contractimpl]
+impl FeeOnTransferToken {
+    pub fn mint(e: Env, to: Address, amount: i128) {
+        let balance = Self::balance(e.clone(), to.clone());
+        e.storage().persistent().set(&to, &(balance + amount));
+    }
+
+    pub fn balance(e: Env, id: Address) -> i128 {
+        e.storage().persistent().get(&id).unwrap_or(0)
+    }
+
+    pub fn transfer(e: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        let fee = amount / 100;
+        let from_balance = Self::balance(e.clone(), from.clone());
+        let to_balance = Self::balance(e.clone(), to.clone());
+        e.storage().persistent().set(&from, &(from_balance - amount));
+        e.storage().persistent().set(&to, &(to_balance + amount - fee));
+    }
+}
+
+fn initialize_engagement_contract(
+    e: &Env,
+    engagement_client: &EngagementContractClient,
+    trustless_work_address: &Address,
+    asset: &Address,
+) -> Address {
+    let admin = Address::generate(e);
+    engagement_client.initialize(
+        &admin,
+        &ProtocolConfig {
+            trustless_work_address: trustless_work_address.clone(),
+            trustless_work_fee: 30,
+            max_platform_fee: 50,
+        },
+    );
+    engagement_client.add_supported_asset(asset, &0);
+    admin
+}
+
+#[test]
+fn test_contract_admin_and_protocol_config() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let trustless_work_address = Address::generate(&env);
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+
+    // Escrows cannot be created before the protocol is configured
+    let escrow_properties = Escrow {
+        engagement_id: String::from_str(&env, "before_initialize"),
+        client: Address::generate(&env),
+        service_provider: Address::generate(&env),
+        approver: None,
+        platform_address: Address::generate(&env),
+        asset: Address::generate(&env),
+        amount: 100_000_000,
+        platform_fee: 5,
+        milestones: vec![&env],
+        release_signers: vec![&env, Address::generate(&env)],
+        release_threshold: 1,
+        dispute_resolver: Address::generate(&env),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    let result = engagement_client.try_initialize_escrow(&escrow_properties);
+    assert_eq!(result, Err(Ok(ContractError::ContractNotInitialized)));
+    assert_eq!(
+        engagement_client.try_get_admin(),
+        Err(Ok(ContractError::ContractNotInitialized))
+    );
+
+    let protocol_config = ProtocolConfig {
+        trustless_work_address: trustless_work_address.clone(),
+        trustless_work_fee: 30,
+        max_platform_fee: 10,
+    };
+
+    let invalid_config = ProtocolConfig {
+        trustless_work_fee: 10_001,
+        ..protocol_config.clone()
+    };
+    let result = engagement_client.try_initialize(&admin, &invalid_config);
+    assert_eq!(result, Err(Ok(ContractError::InvalidProtocolConfig)));
+
+    // Both fees together cannot exceed the released amount
+    let invalid_config = ProtocolConfig {
+        trustless_work_fee: 9_500,
+        max_platform_fee: 10,
+        ..protocol_config.clone()
+    };
+    let result = engagement_client.try_initialize(&admin, &invalid_config);
+    assert_eq!(result, Err(Ok(ContractError::InvalidProtocolConfig)));
+
+    engagement_client.initialize(&admin, &protocol_config);
+    assert_eq!(engagement_client.get_admin(), admin);
+    assert_eq!(engagement_client.get_protocol_config(), protocol_config);
+
+    let result = engagement_client.try_initialize(&new_admin, &protocol_config);
+    assert_eq!(result, Err(Ok(ContractError::ContractAlreadyInitialized)));
+
+    // Configuration changes are authorized by the admin
+    let updated_config = ProtocolConfig {
+        trustless_work_fee: 50,
+        ..protocol_config.clone()
+    };
+    engagement_client.set_protocol_config(&updated_config);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    engagement_contract_address.clone(),
+                    Symbol::new(&env, "set_protocol_config"),
+                    (updated_config.clone(),).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    assert_eq!(engagement_client.get_protocol_config(), updated_config);
+
+    // Admin handover takes two steps
+    let result = engagement_client.try_accept_admin();
+    assert_eq!(result, Err(Ok(ContractError::NoPendingAdmin)));
+
+    engagement_client.transfer_admin(&new_admin);
+    assert_eq!(engagement_client.get_admin(), admin);
+    assert_eq!(engagement_client.get_pending_admin(), Some(new_admin.clone()));
+
+    engagement_client.accept_admin();
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            new_admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    engagement_contract_address.clone(),
+                    Symbol::new(&env, "accept_admin"),
+                    ().into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    assert_eq!(engagement_client.get_admin(), new_admin);
+    assert_eq!(engagement_client.get_pending_admin(), None);
+
+    // A direct handover needs both the current and the new admin and drops
+    // any pending transfer
+    engagement_client.transfer_admin(&Address::generate(&env));
+    engagement_client.set_admin(&admin);
+    let set_admin_invocation = AuthorizedInvocation {
+        function: AuthorizedFunction::Contract((
+            engagement_contract_address.clone(),
+            Symbol::new(&env, "set_admin"),
+            (admin.clone(),).into_val(&env),
+        )),
+        sub_invocations: std::vec![],
+    };
+    assert_eq!(
+        env.auths(),
+        std::vec![
+            (new_admin.clone(), set_admin_invocation.clone()),
+            (admin.clone(), set_admin_invocation),
+        ]
+    );
+    let (_, topics, _) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "admin_changed"), new_admin.clone(), admin.clone()).into_val(&env)
+    );
+    assert_eq!(engagement_client.get_admin(), admin);
+    assert_eq!(engagement_client.get_pending_admin(), None);
+}
+
+#[test]
+fn test_migrate_legacy_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let release_signer_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+
+    let usdc_token = create_usdc_token(&env, &admin);
+    // The escrow funds plus tokens sent to the contract by mistake
+    usdc_token.mint(&engagement_contract_address, &150_000_000);
+
+    // Storage as written by the single escrow layout
+    let engagement_id = String::from_str(&env, "legacy");
+    let legacy_escrow = LegacyEscrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        platform_address: platform_address.clone(),
+        amount: 100_000_000,
+        platform_fee: 5,
+        milestones: vec![
+            &env,
+            LegacyMilestone {
+                description: String::from_str(&env, "First milestone"),
+                status: String::from_str(&env, "Completed"),
+                flag: true,
+            },
+            LegacyMilestone {
+                description: String::from_str(&env, "Second milestone"),
+                status: String::from_str(&env, "Pending"),
+                flag: false,
+            },
+            LegacyMilestone {
+                description: String::from_str(&env, "Third milestone"),
+                status: String::from_str(&env, "Pending"),
+                flag: false,
+            },
+        ],
+        release_signer: release_signer_address.clone(),
+        dispute_resolver: dispute_resolver_address.clone(),
+        dispute_flag: false,
+    };
+    env.as_contract(&engagement_contract_address, || {
+        env.storage().instance().set(&DataKey::Escrow(engagement_id.clone()), &legacy_escrow);
+        env.storage().instance().set(&DataKey::Admin, &true);
+    });
+
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+    assert_eq!(engagement_client.get_schema_version(), 1);
+    assert_eq!(
+        engagement_client.try_get_escrow_by_id(&engagement_id),
+        Err(Ok(ContractError::EscrowNotFound))
+    );
+
+    let result = engagement_client.try_migrate(
+        &vec![&env, String::from_str(&env, "unknown")],
+        &usdc_token.address,
+    );
+    assert_eq!(result, Err(Ok(ContractError::EscrowNotFound)));
+
+    // The contract stays on the legacy version, and takes no new escrows,
+    // until the legacy escrow has been migrated
+    engagement_client.migrate(&vec![&env], &usdc_token.address);
+    assert_eq!(engagement_client.get_schema_version(), 1);
+
+    let new_escrow = Escrow {
+        engagement_id: String::from_str(&env, "new"),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount: 100_000_000,
+        platform_fee: 5,
+        milestones: vec![
+            &env,
+            Milestone {
+                description: String::from_str(&env, "First milestone"),
+                status: String::from_str(&env, "Pending"),
+                amount: 100_000_000,
+                flag: false,
+                released: false,
+                dispute_flag: false,
+            },
+        ],
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    assert_eq!(
+        engagement_client.try_initialize_escrow(&new_escrow),
+        Err(Ok(ContractError::EscrowAlreadyInitialized))
+    );
+
+    engagement_client.migrate(&vec![&env, engagement_id.clone()], &usdc_token.address);
+    assert_eq!(engagement_client.get_schema_version(), 2);
+
+    let escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    assert_eq!(escrow.release_signers, vec![&env, release_signer_address.clone()]);
+    assert_eq!(escrow.release_threshold, 1);
+    assert_eq!(escrow.approver, None);
+    assert_eq!(escrow.milestones.get(0).unwrap().amount, 33_333_333);
+    assert_eq!(escrow.milestones.get(1).unwrap().amount, 33_333_333);
+    assert_eq!(escrow.milestones.get(2).unwrap().amount, 33_333_334);
+    assert!(escrow.milestones.get(0).unwrap().flag);
+
+    // Only the escrow amount is credited to the migrated escrow
+    let escrow_balance = engagement_client.get_escrow_balance(&engagement_id);
+    assert_eq!(escrow_balance.funded, 100_000_000);
+    assert_eq!(escrow_balance.outstanding, 100_000_000);
+
+    assert_eq!(
+        engagement_client.get_escrows_by_client(&client_address, &0, &10),
+        vec![&env, engagement_id.clone()]
+    );
+    assert_eq!(engagement_client.list_escrows(&0, &10, &None).escrows.len(), 1);
+    engagement_client.initialize_escrow(&new_escrow);
+
+    // Running the migration again leaves migrated escrows untouched
+    engagement_client.migrate(&vec![&env, engagement_id.clone()], &usdc_token.address);
+    assert_eq!(engagement_client.get_escrow_balance(&engagement_id), escrow_balance);
+
+    // The migrated escrow goes through the current release flow
+    engagement_client.distribute_escrow_earnings(
+        &engagement_id,
+        &release_signer_address,
+    );
+    assert!(engagement_client.get_escrow_by_id(&engagement_id).milestones.get(0).unwrap().released);
+}
+
+#[test]
+fn test_migrate_paid_out_legacy_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+
+    // Every milestone was approved and the funds have left the contract
+    let engagement_id = String::from_str(&env, "legacy");
+    let legacy_escrow = LegacyEscrow {
+        engagement_id: engagement_id.clone(),
+        client: Address::generate(&env),
+        service_provider: Address::generate(&env),
+        platform_address: Address::generate(&env),
+        amount: 100_000_000,
+        platform_fee: 5,
+        milestones: vec![
+            &env,
+            LegacyMilestone {
+                description: String::from_str(&env, "First milestone"),
+                status: String::from_str(&env, "Completed"),
+                flag: true,
+            },
+            LegacyMilestone {
+                description: String::from_str(&env, "Second milestone"),
+                status: String::from_str(&env, "Completed"),
+                flag: true,
+            },
+        ],
+        release_signer: Address::generate(&env),
+        dispute_resolver: Address::generate(&env),
+        dispute_flag: false,
+    };
+    env.as_contract(&engagement_contract_address, || {
+        env.storage().instance().set(&DataKey::Escrow(engagement_id.clone()), &legacy_escrow);
+        env.storage().instance().set(&DataKey::Admin, &true);
+    });
+
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+    engagement_client.migrate(&vec![&env, engagement_id.clone()], &usdc_token.address);
+
+    let escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    assert!(escrow.milestones.iter().all(|milestone| milestone.flag && milestone.released));
+    assert_eq!(engagement_client.get_escrow_balance(&engagement_id).funded, 0);
+    assert_eq!(engagement_client.get_escrow_status(&engagement_id), EscrowStatus::Completed);
+}
+
+#[test]
+fn test_upgrade_requires_initialized_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+
+    let new_wasm_hash = BytesN::from_array(&env, &[0; 32]);
+    let result = engagement_client.try_upgrade(&new_wasm_hash);
+    assert_eq!(result, Err(Ok(ContractError::ContractNotInitialized)));
+
+    // A freshly initialized contract starts on the current layout
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+    assert_eq!(engagement_client.get_schema_version(), 2);
+}
+
+#[test]
+fn test_pause_blocks_fund_movements_and_amendments() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let token_admin = Address::generate(&env);
+    let guardian = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let release_signer_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+
+    let usdc_token = create_usdc_token(&env, &token_admin);
+    let amount: i128 = 100_000_000;
+    usdc_token.mint(&client_address, &amount);
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let admin = initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+
+    let engagement_id = String::from_str(&env, "paused");
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee: 5,
+        milestones: vec![
+            &env,
+            Milestone {
+                description: String::from_str(&env, "First milestone"),
+                status: String::from_str(&env, "Completed"),
+                amount: 50_000_000,
+                flag: false,
+                released: false,
+                dispute_flag: false,
+            },
+            Milestone {
+                description: String::from_str(&env, "Second milestone"),
+                status: String::from_str(&env, "Pending"),
+                amount: 50_000_000,
+                flag: false,
+                released: false,
+                dispute_flag: false,
+            },
+        ],
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    engagement_client.initialize_escrow(&escrow_properties);
+    engagement_client.change_milestone_flag(&engagement_id, &0, &true, &client_address);
+    engagement_client.fund_escrow(&engagement_id, &client_address, &(amount / 2));
+    engagement_client.change_dispute_flag(&engagement_id, &1_i128, &client_address);
+
+    // Only the admin or the guardian can pause
+    let result = engagement_client.try_pause(&guardian);
+    assert_eq!(result, Err(Ok(ContractError::OnlyAdminOrGuardianCanPause)));
+
+    engagement_client.set_guardian(&Some(guardian.clone()));
+    assert_eq!(engagement_client.get_guardian(), Some(guardian.clone()));
+
+    engagement_client.pause(&guardian);
+    assert!(engagement_client.is_paused());
+    let (_, topics, _) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "contract_paused"), guardian.clone()).into_val(&env)
+    );
+
+    assert_eq!(
+        engagement_client.try_fund_escrow(&engagement_id, &client_address, &(amount / 2)),
+        Err(Ok(ContractError::ContractPaused))
+    );
+    assert_eq!(
+        engagement_client.try_distribute_escrow_earnings(&engagement_id, &release_signer_address),
+        Err(Ok(ContractError::ContractPaused))
+    );
+    assert_eq!(
+        engagement_client.try_resolving_disputes(
+            &engagement_id,
+            &1_i128,
+            &dispute_resolver_address,
+            &0,
+            &50_000_000,
+        ),
+        Err(Ok(ContractError::ContractPaused))
+    );
+    assert_eq!(
+        engagement_client.try_change_escrow_properties(&escrow_properties),
+        Err(Ok(ContractError::ContractPaused))
+    );
+    assert_eq!(
+        engagement_client.try_update_approver(&engagement_id, &client_address, &Address::generate(&env)),
+        Err(Ok(ContractError::ContractPaused))
+    );
+    assert_eq!(
+        engagement_client.try_update_release_signer(&engagement_id, &release_signer_address, &Address::generate(&env)),
+        Err(Ok(ContractError::ContractPaused))
+    );
+    assert_eq!(
+        engagement_client.try_update_dispute_resolver(&engagement_id, &Address::generate(&env)),
+        Err(Ok(ContractError::ContractPaused))
+    );
+
+    // Read-only calls keep working
+    assert_eq!(engagement_client.get_escrow_by_id(&engagement_id).engagement_id, engagement_id);
+    assert_eq!(engagement_client.get_escrow_balance(&engagement_id).funded, amount / 2);
+
+    // The guardian cannot resume the contract
+    engagement_client.unpause();
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    engagement_contract_address.clone(),
+                    Symbol::new(&env, "unpause"),
+                    ().into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    assert!(!engagement_client.is_paused());
+
+    engagement_client.distribute_escrow_earnings(&engagement_id, &release_signer_address);
+    assert_eq!(usdc_token.balance(&engagement_contract_address), 0);
+}
+
+#[test]
+fn test_escrow_factory_and_wasm_hash_registry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    let admin = initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+
+    let engagement_id = String::from_str(&env, "factory");
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: Address::generate(&env),
+        service_provider: Address::generate(&env),
+        approver: None,
+        platform_address: Address::generate(&env),
+        asset: usdc_token.address.clone(),
+        amount: 100_000_000,
+        platform_fee: 5,
+        milestones: vec![&env],
+        release_signers: vec![&env, Address::generate(&env)],
+        release_threshold: 1,
+        dispute_resolver: Address::generate(&env),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+
+    // Nothing can be deployed until the admin registers the escrow wasm
+    let result = engagement_client.try_deploy_escrow(&escrow_properties);
+    assert_eq!(result, Err(Ok(ContractError::EscrowWasmHashNotSet)));
+    assert_eq!(
+        engagement_client.try_get_escrow_wasm_hash(),
+        Err(Ok(ContractError::EscrowWasmHashNotSet))
+    );
+
+    let first_wasm_hash = BytesN::from_array(&env, &[1; 32]);
+    let second_wasm_hash = BytesN::from_array(&env, &[2; 32]);
+    engagement_client.register_wasm_hash(&first_wasm_hash, &1);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    engagement_contract_address.clone(),
+                    Symbol::new(&env, "register_wasm_hash"),
+                    (first_wasm_hash.clone(), 1_u32).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    engagement_client.register_wasm_hash(&second_wasm_hash, &2);
+
+    // Escrows are deployed from the highest approved version
+    assert_eq!(
+        engagement_client.get_escrow_wasm_hash(),
+        WasmHashEntry {
+            wasm_hash: second_wasm_hash.clone(),
+            version: 2,
+        }
+    );
+    assert_eq!(engagement_client.get_wasm_hash_version(&first_wasm_hash), Some(1));
+    assert_eq!(engagement_client.get_approved_wasm_hashes().len(), 2);
+
+    engagement_client.revoke_wasm_hash(&second_wasm_hash);
+    assert_eq!(engagement_client.get_wasm_hash_version(&second_wasm_hash), None);
+    assert_eq!(engagement_client.get_escrow_wasm_hash().wasm_hash, first_wasm_hash);
+    assert_eq!(
+        engagement_client.try_revoke_wasm_hash(&second_wasm_hash),
+        Err(Ok(ContractError::WasmHashNotApproved))
+    );
+
+    // The generic deployer refuses hashes that are not on the list
+    let result = engagement_client.try_deploy(
+        &engagement_contract_address,
+        &second_wasm_hash,
+        &BytesN::from_array(&env, &[0; 32]),
+        &Symbol::new(&env, "initialize_escrow"),
+        &vec![&env, escrow_properties.into_val(&env)],
+    );
+    assert_eq!(result.err(), Some(Ok(ContractError::WasmHashNotApproved)));
+
+    assert_eq!(
+        engagement_client.try_get_deployed_escrow(&engagement_id),
+        Err(Ok(ContractError::EscrowNotFound))
+    );
+
+    let escrow_properties = Escrow {
+        milestones: vec![
+            &env,
+            Milestone {
+                description: String::from_str(&env, "First milestone"),
+                status: String::from_str(&env, "Pending"),
+                amount: 100_000_000,
+                flag: false,
+                released: false,
+                dispute_flag: false,
+            },
+        ],
+        ..escrow_properties
+    };
+    // Running the instance from wasm takes more than the default budget
+    env.budget().reset_unlimited();
+    let escrow_wasm_hash = env.deployer().upload_contract_wasm(escrow_wasm::WASM);
+    engagement_client.register_wasm_hash(&escrow_wasm_hash, &3);
+
+    // The address of an escrow instance is known before it is deployed, and
+    // nobody but the admin can deploy under the factory to take it first
+    let salt: BytesN<32> = env.crypto().sha256(&engagement_id.clone().to_xdr(&env)).into();
+    let instance_address = env
+        .deployer()
+        .with_address(engagement_contract_address.clone(), salt.clone())
+        .deployed_address();
+    let attacker = Address::generate(&env);
+    let protocol_config = engagement_client.get_protocol_config();
+    let initialize = Symbol::new(&env, "initialize");
+    let init_args = vec![&env, attacker.into_val(&env), protocol_config.into_val(&env)];
+    env.mock_auths(&[MockAuth {
+        address: &attacker,
+        invoke: &MockAuthInvoke {
+            contract: &instance_address,
+            fn_name: "initialize",
+            args: (attacker.clone(), protocol_config.clone()).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    let result = engagement_client.try_deploy(
+        &engagement_contract_address,
+        &escrow_wasm_hash,
+        &salt,
+        &initialize,
+        &init_args,
+    );
+    assert!(result.is_err());
+    env.mock_all_auths();
+
+    // and the factory deploys nothing while it is paused
+    engagement_client.pause(&admin);
+    let result = engagement_client.try_deploy(
+        &engagement_contract_address,
+        &escrow_wasm_hash,
+        &salt,
+        &initialize,
+        &init_args,
+    );
+    assert_eq!(result.err(), Some(Ok(ContractError::ContractPaused)));
+    engagement_client.unpause();
+
+    // deploy_escrow deploys the latest approved wasm, makes the factory the
+    // admin of the instance and creates the escrow in it. The platform
+    // authorizes the whole set-up with its deploy_escrow call.
+    let deployed_address = engagement_client.deploy_escrow(&escrow_properties);
+    assert_eq!(deployed_address, instance_address);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            escrow_properties.platform_address.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    engagement_contract_address.clone(),
+                    Symbol::new(&env, "deploy_escrow"),
+                    (escrow_properties.clone(),).into_val(&env),
+                )),
+                sub_invocations: std::vec![AuthorizedInvocation {
+                    function: AuthorizedFunction::Contract((
+                        instance_address.clone(),
+                        Symbol::new(&env, "initialize_escrow"),
+                        (escrow_properties.clone(),).into_val(&env),
+                    )),
+                    sub_invocations: std::vec![],
+                }],
+            }
+        )]
+    );
+    let instance_client = EngagementContractClient::new(&env, &instance_address);
+    assert_eq!(instance_client.get_admin(), engagement_contract_address);
+    assert_eq!(instance_client.get_protocol_config(), protocol_config);
+    assert_eq!(instance_client.get_supported_assets(), engagement_client.get_supported_assets());
+    assert_eq!(instance_client.get_escrow_by_id(&engagement_id), escrow_properties);
+    assert_eq!(engagement_client.get_deployed_escrow(&engagement_id), instance_address);
+    assert_eq!(
+        engagement_client.try_deploy_escrow(&escrow_properties),
+        Err(Ok(ContractError::EscrowAlreadyInitialized))
+    );
+
+    // The factory admin reaches the instance through the factory
+    engagement_client.pause_deployed_escrow(&engagement_id, &admin);
+    assert!(instance_client.is_paused());
+    assert!(!engagement_client.is_paused());
+    assert_eq!(
+        engagement_client.try_pause_deployed_escrow(&engagement_id, &Address::generate(&env)),
+        Err(Ok(ContractError::OnlyAdminOrGuardianCanPause))
+    );
+
+    engagement_client.unpause_deployed_escrow(&engagement_id);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    engagement_contract_address.clone(),
+                    Symbol::new(&env, "unpause_deployed_escrow"),
+                    (engagement_id.clone(),).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    assert!(!instance_client.is_paused());
+
+    assert_eq!(
+        engagement_client.try_upgrade_deployed_escrow(&engagement_id, &second_wasm_hash),
+        Err(Ok(ContractError::WasmHashNotApproved))
+    );
+    engagement_client.upgrade_deployed_escrow(&engagement_id, &escrow_wasm_hash);
+
+    engagement_client.migrate_deployed_escrow(&engagement_id, &vec![&env], &usdc_token.address);
+    assert_eq!(instance_client.get_schema_version(), 2);
+
+    assert_eq!(
+        engagement_client.try_unpause_deployed_escrow(&String::from_str(&env, "unknown")),
+        Err(Ok(ContractError::EscrowNotFound))
+    );
+
+    // The factory admin has no direct say over the instance
+    assert_eq!(
+        instance_client.try_pause(&admin),
+        Err(Ok(ContractError::OnlyAdminOrGuardianCanPause))
+    );
+}
+
 #[test]
 fn test_initialize_excrow() {
     let env = Env::default();
@@ -31,30 +819,58 @@ fn test_initialize_excrow() {
         Milestone {
             description: String::from_str(&env, "First milestone"),
             status: String::from_str(&env, "Pending"),
+            amount: 50_000_000,
             flag: false,
+            released: false,
+            dispute_flag: false,
         },
         Milestone {
             description: String::from_str(&env, "Second milestone"),
             status: String::from_str(&env, "Pending"),
+            amount: 50_000_000,
             flag: false,
+            released: false,
+            dispute_flag: false,
         },
     ];
 
     let engagement_contract_address = env.register_contract(None, EngagementContract);
     let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
 
     let engagement_id = String::from_str(&env, "41431");
 
-    let engagement_id = engagement_client.initialize_escrow(
-        &engagement_id.clone(),
-        &client_address,
-        &service_provider_address,
-        &platform_address,
-        &amount,
-        &platform_fee,
-        &milestones,
-        &release_signer_address,
-        &dispute_resolver_address,
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee,
+        milestones: milestones.clone(),
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    let engagement_id = engagement_client.initialize_escrow(&escrow_properties);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            platform_address.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    engagement_contract_address.clone(),
+                    Symbol::new(&env, "initialize_escrow"),
+                    (escrow_properties.clone(),).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
     );
 
     let escrow = engagement_client.get_escrow_by_id(&engagement_id);
@@ -65,146 +881,2606 @@ fn test_initialize_excrow() {
     assert_eq!(escrow.amount, amount);
     assert_eq!(escrow.platform_fee, platform_fee);
     assert_eq!(escrow.milestones, milestones);
-    assert_eq!(escrow.release_signer, release_signer_address);
+    assert_eq!(escrow.release_signers, vec![&env, release_signer_address.clone()]);
     assert_eq!(escrow.dispute_resolver, dispute_resolver_address);
+    assert_eq!(escrow.approver, None);
 }
 
 #[test]
-fn test_change_escrow_properties() {
+fn test_supported_assets_and_fee_on_transfer_funding() {
     let env = Env::default();
     env.mock_all_auths();
 
     let client_address = Address::generate(&env);
-    let platform_address = Address::generate(&env);
     let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
     let release_signer_address = Address::generate(&env);
     let dispute_resolver_address = Address::generate(&env);
 
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    let admin = initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+
+    let fee_token_address = env.register_contract(None, FeeOnTransferToken);
+    let fee_token = FeeOnTransferTokenClient::new(&env, &fee_token_address);
     let amount: i128 = 100_000_000;
-    let platform_fee = (0.3 * 10i128.pow(18) as f64) as i128;
+    fee_token.mint(&client_address, &amount);
+
+    let escrow_properties = Escrow {
+        engagement_id: String::from_str(&env, "fee_on_transfer"),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: fee_token_address.clone(),
+        amount,
+        platform_fee: 5,
+        milestones: vec![
+            &env,
+            Milestone {
+                description: String::from_str(&env, "First milestone"),
+                status: String::from_str(&env, "Pending"),
+                amount,
+                flag: false,
+                released: false,
+                dispute_flag: false,
+            },
+        ],
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+
+    // Escrows can only be created in listed assets
+    let result = engagement_client.try_initialize_escrow(&escrow_properties);
+    assert_eq!(result, Err(Ok(ContractError::AssetNotSupported)));
+
+    engagement_client.add_supported_asset(&fee_token_address, &0);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    engagement_contract_address.clone(),
+                    Symbol::new(&env, "add_supported_asset"),
+                    (fee_token_address.clone(), 0_i128).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    let (_, topics, _) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "asset_supported"), admin.clone(), fee_token_address.clone()).into_val(&env)
+    );
+    assert_eq!(
+        engagement_client.get_supported_assets(),
+        vec![
+            &env,
+            SupportedAsset { asset: usdc_token.address.clone(), minimum_amount: 0 },
+            SupportedAsset { asset: fee_token_address.clone(), minimum_amount: 0 },
+        ]
+    );
 
-    let initial_milestones = vec![
-        &env,
-        Milestone {
-            description: String::from_str(&env, "First milestone"),
-            status: String::from_str(&env, "Pending"),
-            flag: false,
-        },
-        Milestone {
-            description: String::from_str(&env, "Second milestone"),
-            status: String::from_str(&env, "Pending"),
-            flag: false,
-        },
-    ];
+    let engagement_id = engagement_client.initialize_escrow(&escrow_properties);
+
+    // Only what reaches the contract is credited to the escrow
+    engagement_client.fund_escrow(&engagement_id, &client_address, &(amount / 2));
+    let escrow_balance = engagement_client.get_escrow_balance(&engagement_id);
+    assert_eq!(escrow_balance.funded, 49_500_000);
+    assert_eq!(escrow_balance.outstanding, fee_token.balance(&engagement_contract_address));
+
+    // Deposits must be positive and cannot overflow the funded total
+    assert_eq!(
+        engagement_client.try_fund_escrow(&engagement_id, &client_address, &-1),
+        Err(Ok(ContractError::NegativeAmountNotAllowed))
+    );
+    assert_eq!(
+        engagement_client.try_fund_escrow(&engagement_id, &client_address, &0),
+        Err(Ok(ContractError::AmountCannotBeZero))
+    );
+    assert_eq!(
+        engagement_client.try_fund_escrow(&engagement_id, &client_address, &i128::MAX),
+        Err(Ok(ContractError::AmountToDepositGreatherThanEscrowAmount))
+    );
+
+    // Removing an asset does not affect escrows already created in it
+    engagement_client.remove_supported_asset(&fee_token_address);
+    assert_eq!(engagement_client.get_supported_assets().len(), 1);
+    assert_eq!(
+        engagement_client.try_remove_supported_asset(&fee_token_address),
+        Err(Ok(ContractError::AssetNotSupported))
+    );
+
+    engagement_client.fund_escrow(&engagement_id, &client_address, &(amount / 4));
+    assert_eq!(engagement_client.get_escrow_balance(&engagement_id).funded, 74_250_000);
+
+    // The asset of an existing escrow cannot be changed
+    engagement_client.change_escrow_properties(&Escrow {
+        asset: usdc_token.address.clone(),
+        ..escrow_properties.clone()
+    });
+    assert_eq!(engagement_client.get_escrow_by_id(&engagement_id).asset, fee_token_address);
+}
+
+#[test]
+fn test_escrows_in_different_assets() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let release_signer_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+    let trustless_work_address = Address::generate(&env);
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    initialize_engagement_contract(&env, &engagement_client, &trustless_work_address, &usdc_token.address);
+
+    let eurc_token = TokenClient::new(&env, &env.register_contract(None, Token {}));
+    eurc_token.initialize(&Address::generate(&env), &6, &"EURC".into_val(&env), &"EURC".into_val(&env));
+    engagement_client.add_supported_asset(&eurc_token.address, &50_000_000);
+
+    let usdc_amount: i128 = 100_000_000;
+    let eurc_amount: i128 = 20_000_000;
+    usdc_token.mint(&client_address, &usdc_amount);
+    eurc_token.mint(&client_address, &eurc_amount);
+
+    let milestone = Milestone {
+        description: String::from_str(&env, "Only milestone"),
+        status: String::from_str(&env, "Completed"),
+        amount: usdc_amount,
+        flag: false,
+        released: false,
+        dispute_flag: false,
+    };
+    let usdc_escrow = Escrow {
+        engagement_id: String::from_str(&env, "usdc_escrow"),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount: usdc_amount,
+        platform_fee: 5,
+        milestones: vec![&env, milestone.clone()],
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    let eurc_escrow = Escrow {
+        engagement_id: String::from_str(&env, "eurc_escrow"),
+        asset: eurc_token.address.clone(),
+        amount: eurc_amount,
+        milestones: vec![&env, Milestone { amount: eurc_amount, ..milestone.clone() }],
+        ..usdc_escrow.clone()
+    };
+
+    // Each asset has its own minimum escrow amount
+    let result = engagement_client.try_initialize_escrow(&eurc_escrow);
+    assert_eq!(result, Err(Ok(ContractError::AmountBelowAssetMinimum)));
+
+    engagement_client.add_supported_asset(&eurc_token.address, &10_000_000);
+    assert_eq!(
+        engagement_client.get_supported_assets().get(1),
+        Some(SupportedAsset { asset: eurc_token.address.clone(), minimum_amount: 10_000_000 })
+    );
+
+    engagement_client.initialize_escrow(&usdc_escrow);
+    engagement_client.initialize_escrow(&eurc_escrow);
+
+    let result = engagement_client.try_change_escrow_properties(&Escrow {
+        amount: 5_000_000,
+        milestones: vec![&env, Milestone { amount: 5_000_000, ..milestone.clone() }],
+        ..eurc_escrow.clone()
+    });
+    assert_eq!(result, Err(Ok(ContractError::AmountBelowAssetMinimum)));
+
+    engagement_client.change_milestone_flag(&usdc_escrow.engagement_id, &0, &true, &client_address);
+    engagement_client.change_milestone_flag(&eurc_escrow.engagement_id, &0, &true, &client_address);
+    engagement_client.fund_escrow(&usdc_escrow.engagement_id, &client_address, &usdc_amount);
+    engagement_client.fund_escrow(&eurc_escrow.engagement_id, &client_address, &eurc_amount);
+    assert_eq!(usdc_token.balance(&engagement_contract_address), usdc_amount);
+    assert_eq!(eurc_token.balance(&engagement_contract_address), eurc_amount);
+
+    // Fees and payouts are made in the asset of each escrow
+    engagement_client.distribute_escrow_earnings(&eurc_escrow.engagement_id, &release_signer_address);
+    assert_eq!(eurc_token.balance(&engagement_contract_address), 0);
+    assert_eq!(eurc_token.balance(&trustless_work_address), 60_000);
+    assert_eq!(eurc_token.balance(&platform_address), 1_000_000);
+    assert_eq!(eurc_token.balance(&service_provider_address), 18_940_000);
+    assert_eq!(usdc_token.balance(&engagement_contract_address), usdc_amount);
+
+    engagement_client.distribute_escrow_earnings(&usdc_escrow.engagement_id, &release_signer_address);
+    assert_eq!(usdc_token.balance(&engagement_contract_address), 0);
+    assert_eq!(usdc_token.balance(&trustless_work_address), 300_000);
+    assert_eq!(usdc_token.balance(&platform_address), 5_000_000);
+    assert_eq!(usdc_token.balance(&service_provider_address), 94_700_000);
+}
+
+#[test]
+fn test_retainer_escrow_periodic_releases() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let release_signer_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+
+    let period_amount: i128 = 30_000_000;
+    let period_duration: u64 = 30 * 24 * 60 * 60;
+    let start_time: u64 = 1_000;
+    usdc_token.mint(&client_address, &(period_amount * 3));
+
+    let schedule = RetainerSchedule {
+        period_amount,
+        period_count: 3,
+        period_duration,
+        start_time,
+        periods_released: 0,
+    };
+    let escrow_properties = Escrow {
+        engagement_id: String::from_str(&env, "retainer"),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount: period_amount * 3,
+        platform_fee: 5,
+        milestones: vec![&env],
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Retainer(schedule.clone()),
+        challenge_period: 0,
+    };
+
+    // The periods must add up to the escrow amount
+    let result = engagement_client.try_initialize_escrow(&Escrow {
+        amount: period_amount * 2,
+        ..escrow_properties.clone()
+    });
+    assert_eq!(result, Err(Ok(ContractError::InvalidEscrowSchedule)));
+
+    let engagement_id = engagement_client.initialize_escrow(&escrow_properties);
+    engagement_client.fund_escrow(&engagement_id, &client_address, &(period_amount * 3));
+
+    // A period can only be released once it has ended
+    env.ledger().with_mut(|li| li.timestamp = start_time + 100);
+    assert_eq!(
+        engagement_client.try_release_retainer_period(&engagement_id, &None),
+        Err(Ok(ContractError::ReleaseNotYetDue))
+    );
+    assert_eq!(
+        engagement_client.try_distribute_escrow_earnings(&engagement_id, &release_signer_address),
+        Err(Ok(ContractError::NotSupportedForEscrowMode))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = start_time + period_duration);
+    engagement_client.release_retainer_period(&engagement_id, &None);
+    assert_eq!(usdc_token.balance(&service_provider_address), 28_410_000);
+    assert_eq!(usdc_token.balance(&platform_address), 1_500_000);
+    assert_eq!(
+        engagement_client.try_release_retainer_period(&engagement_id, &None),
+        Err(Ok(ContractError::ReleaseNotYetDue))
+    );
+
+    // Stopping keeps the period that has already ended and refunds the rest
+    env.ledger().with_mut(|li| li.timestamp = start_time + 2 * period_duration + 10);
+    engagement_client.stop_retainer(&engagement_id);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            client_address.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    engagement_contract_address.clone(),
+                    Symbol::new(&env, "stop_retainer"),
+                    (engagement_id.clone(),).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "escrow_cancelled"), engagement_id.clone(), client_address.clone()).into_val(&env)
+    );
+    let cancelled: EscrowCancelledEvent = data.into_val(&env);
+    assert_eq!(cancelled, EscrowCancelledEvent { amount: 2 * period_amount, refunded_amount: period_amount });
+    assert_eq!(usdc_token.balance(&client_address), period_amount);
+
+    let stopped_escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    assert_eq!(stopped_escrow.amount, period_amount * 2);
+    assert_eq!(
+        stopped_escrow.mode,
+        EscrowMode::Retainer(RetainerSchedule { period_count: 2, periods_released: 1, ..schedule })
+    );
+    assert_eq!(
+        engagement_client.try_stop_retainer(&engagement_id),
+        Err(Ok(ContractError::NothingToRelease))
+    );
+
+    engagement_client.release_retainer_period(&engagement_id, &None);
+    assert_eq!(usdc_token.balance(&service_provider_address), 56_820_000);
+    assert_eq!(usdc_token.balance(&engagement_contract_address), 0);
+    assert_eq!(engagement_client.get_escrow_status(&engagement_id), EscrowStatus::Completed);
+    assert_eq!(
+        engagement_client.try_release_retainer_period(&engagement_id, &None),
+        Err(Ok(ContractError::NothingToRelease))
+    );
+}
+
+
+#[test]
+fn test_release_signers_can_release_a_retainer_period_early() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let first_signer = Address::generate(&env);
+    let second_signer = Address::generate(&env);
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+
+    let period_amount: i128 = 30_000_000;
+    let period_duration: u64 = 30 * 24 * 60 * 60;
+    let start_time: u64 = 1_000;
+    usdc_token.mint(&client_address, &(period_amount * 2));
+
+    let schedule = RetainerSchedule {
+        period_amount,
+        period_count: 2,
+        period_duration,
+        start_time,
+        periods_released: 0,
+    };
+    let engagement_id = engagement_client.initialize_escrow(&Escrow {
+        engagement_id: String::from_str(&env, "retainer"),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: Address::generate(&env),
+        asset: usdc_token.address.clone(),
+        amount: period_amount * 2,
+        platform_fee: 5,
+        milestones: vec![&env],
+        release_signers: vec![&env, first_signer.clone(), second_signer.clone()],
+        release_threshold: 2,
+        dispute_resolver: Address::generate(&env),
+        mode: EscrowMode::Retainer(schedule.clone()),
+        challenge_period: 0,
+    });
+    engagement_client.fund_escrow(&engagement_id, &client_address, &(period_amount * 2));
+    env.ledger().with_mut(|li| li.timestamp = start_time + 100);
+
+    assert_eq!(
+        engagement_client.try_release_retainer_period(&engagement_id, &Some(Address::generate(&env))),
+        Err(Ok(ContractError::OnlyReleaseSignerCanClaimEarnings))
+    );
+
+    // Before the period ends it takes the threshold of release signers
+    engagement_client.release_retainer_period(&engagement_id, &Some(first_signer.clone()));
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            first_signer.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    engagement_contract_address.clone(),
+                    Symbol::new(&env, "release_retainer_period"),
+                    (engagement_id.clone(), Some(first_signer.clone())).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    let (_, topics, _) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "release_approved"), engagement_id.clone(), first_signer.clone()).into_val(&env)
+    );
+    assert_eq!(usdc_token.balance(&service_provider_address), 0);
+    assert_eq!(engagement_client.get_release_approvals(&engagement_id), vec![&env, first_signer.clone()]);
+    assert_eq!(
+        engagement_client.try_release_retainer_period(&engagement_id, &Some(first_signer.clone())),
+        Err(Ok(ContractError::ReleaseAlreadyApprovedBySigner))
+    );
+
+    engagement_client.release_retainer_period(&engagement_id, &Some(second_signer.clone()));
+    assert_eq!(usdc_token.balance(&service_provider_address), 28_410_000);
+    assert_eq!(engagement_client.get_release_approvals(&engagement_id).len(), 0);
+    assert_eq!(
+        engagement_client.get_escrow_by_id(&engagement_id).mode,
+        EscrowMode::Retainer(RetainerSchedule { periods_released: 1, ..schedule })
+    );
+
+    // The next period is still only due for everyone else once it has ended
+    assert_eq!(
+        engagement_client.try_release_retainer_period(&engagement_id, &None),
+        Err(Ok(ContractError::ReleaseNotYetDue))
+    );
+    env.ledger().with_mut(|li| li.timestamp = start_time + 2 * period_duration);
+    engagement_client.release_retainer_period(&engagement_id, &None);
+    assert_eq!(usdc_token.balance(&service_provider_address), 56_820_000);
+}
+
+#[test]
+fn test_stream_escrow_vesting() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let release_signer_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+
+    let amount: i128 = 100_000_000;
+    usdc_token.mint(&client_address, &amount);
+
+    let schedule = StreamSchedule {
+        start_time: 1_000,
+        cliff_time: 1_100,
+        end_time: 2_000,
+        withdrawn: 0,
+        paused_at: None,
+        paused_duration: 0,
+    };
+    let escrow_properties = Escrow {
+        engagement_id: String::from_str(&env, "stream"),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee: 5,
+        milestones: vec![&env],
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Stream(schedule.clone()),
+        challenge_period: 0,
+    };
+
+    let result = engagement_client.try_initialize_escrow(&Escrow {
+        mode: EscrowMode::Stream(StreamSchedule { cliff_time: 2_500, ..schedule.clone() }),
+        ..escrow_properties.clone()
+    });
+    assert_eq!(result, Err(Ok(ContractError::InvalidEscrowSchedule)));
+
+    let engagement_id = engagement_client.initialize_escrow(&escrow_properties);
+    engagement_client.fund_escrow(&engagement_id, &client_address, &amount);
+
+    // Nothing vests before the cliff
+    env.ledger().with_mut(|li| li.timestamp = 1_050);
+    assert_eq!(engagement_client.get_vested_amount(&engagement_id), 0);
+    assert_eq!(
+        engagement_client.try_withdraw_vested(&engagement_id),
+        Err(Ok(ContractError::NothingToRelease))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 1_250);
+    engagement_client.withdraw_vested(&engagement_id);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            service_provider_address.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    engagement_contract_address.clone(),
+                    Symbol::new(&env, "withdraw_vested"),
+                    (engagement_id.clone(),).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    assert_eq!(usdc_token.balance(&service_provider_address), 23_675_000);
+    assert_eq!(engagement_client.get_escrow_balance(&engagement_id).released, 25_000_000);
+
+    // The amount of a stream is fixed once it is created
+    engagement_client.change_escrow_properties(&Escrow { amount: amount * 2, ..escrow_properties.clone() });
+    assert_eq!(engagement_client.get_escrow_by_id(&engagement_id).amount, amount);
+    assert_eq!(engagement_client.get_vested_amount(&engagement_id), 25_000_000);
+
+    // A dispute stops accrual until it is resolved
+    assert_eq!(
+        engagement_client.try_resolve_stream_dispute(&engagement_id, &false),
+        Err(Ok(ContractError::StreamNotInDispute))
+    );
+    env.ledger().with_mut(|li| li.timestamp = 1_300);
+    engagement_client.open_stream_dispute(&engagement_id);
+    assert_eq!(engagement_client.get_escrow_status(&engagement_id), EscrowStatus::InDispute);
+    assert_eq!(
+        engagement_client.try_open_stream_dispute(&engagement_id),
+        Err(Ok(ContractError::EscrowOpenedForDisputeResolution))
+    );
+
+    // Neither the release signers nor the dispute resolver can be rotated mid dispute
+    assert_eq!(
+        engagement_client.try_update_release_signer(&engagement_id, &release_signer_address, &Address::generate(&env)),
+        Err(Ok(ContractError::EscrowOpenedForDisputeResolution))
+    );
+    assert_eq!(
+        engagement_client.try_update_dispute_resolver(&engagement_id, &Address::generate(&env)),
+        Err(Ok(ContractError::EscrowOpenedForDisputeResolution))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 1_600);
+    assert_eq!(engagement_client.get_vested_amount(&engagement_id), 30_000_000);
+    engagement_client.withdraw_vested(&engagement_id);
+    assert_eq!(usdc_token.balance(&service_provider_address), 28_410_000);
+
+    // Resuming pushes the rest of the schedule back by the time in dispute
+    engagement_client.resolve_stream_dispute(&engagement_id, &false);
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&env, "stream_dispute_resolved"),
+            engagement_id.clone(),
+            dispute_resolver_address.clone(),
+        ).into_val(&env)
+    );
+    let resolved: StreamDisputeResolvedEvent = data.into_val(&env);
+    assert!(!resolved.halted);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_700);
+    assert_eq!(engagement_client.get_vested_amount(&engagement_id), 40_000_000);
+
+    // Halting keeps what has vested for the service provider and refunds the rest
+    engagement_client.halt_stream(&engagement_id);
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "escrow_cancelled"), engagement_id.clone(), client_address.clone()).into_val(&env)
+    );
+    let cancelled: EscrowCancelledEvent = data.into_val(&env);
+    assert_eq!(cancelled, EscrowCancelledEvent { amount: 40_000_000, refunded_amount: 60_000_000 });
+    assert_eq!(usdc_token.balance(&client_address), 60_000_000);
+    assert_eq!(engagement_client.get_escrow_by_id(&engagement_id).amount, 40_000_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 2_500);
+    assert_eq!(engagement_client.get_vested_amount(&engagement_id), 40_000_000);
+    assert_eq!(
+        engagement_client.try_halt_stream(&engagement_id),
+        Err(Ok(ContractError::NothingToRelease))
+    );
+
+    engagement_client.withdraw_vested(&engagement_id);
+    assert_eq!(usdc_token.balance(&service_provider_address), 37_880_000);
+    assert_eq!(usdc_token.balance(&engagement_contract_address), 0);
+    assert_eq!(engagement_client.get_escrow_status(&engagement_id), EscrowStatus::Completed);
+}
+
+#[test]
+fn test_dispute_resolver_can_halt_a_stream() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+
+    let amount: i128 = 100_000_000;
+    usdc_token.mint(&client_address, &amount);
+
+    let escrow_properties = Escrow {
+        engagement_id: String::from_str(&env, "halted_stream"),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: Address::generate(&env),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee: 5,
+        milestones: vec![&env],
+        release_signers: vec![&env, Address::generate(&env)],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Stream(StreamSchedule {
+            start_time: 0,
+            cliff_time: 0,
+            end_time: 1_000,
+            withdrawn: 0,
+            paused_at: None,
+            paused_duration: 0,
+        }),
+        challenge_period: 0,
+    };
+    let engagement_id = engagement_client.initialize_escrow(&escrow_properties);
+    engagement_client.fund_escrow(&engagement_id, &client_address, &amount);
+
+    env.ledger().with_mut(|li| li.timestamp = 200);
+    engagement_client.open_stream_dispute(&engagement_id);
+
+    // The stream ends at the time the dispute was opened
+    env.ledger().with_mut(|li| li.timestamp = 900);
+    engagement_client.resolve_stream_dispute(&engagement_id, &true);
+    assert_eq!(usdc_token.balance(&client_address), 80_000_000);
+    assert_eq!(engagement_client.get_vested_amount(&engagement_id), 20_000_000);
+    assert_eq!(engagement_client.get_escrow_status(&engagement_id), EscrowStatus::Funded);
+
+    engagement_client.withdraw_vested(&engagement_id);
+    assert_eq!(engagement_client.get_escrow_balance(&engagement_id).outstanding, 0);
+    assert_eq!(engagement_client.get_escrow_status(&engagement_id), EscrowStatus::Completed);
+}
+
+#[test]
+fn test_escrow_property_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let release_signer_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+
+    let milestone = Milestone {
+        description: String::from_str(&env, "First milestone"),
+        status: String::from_str(&env, "Pending"),
+        amount: 60_000_000,
+        flag: false,
+        released: false,
+        dispute_flag: false,
+    };
+    let escrow_properties = Escrow {
+        engagement_id: String::from_str(&env, "validated"),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount: 100_000_000,
+        platform_fee: 5,
+        milestones: vec![
+            &env,
+            milestone.clone(),
+            Milestone {
+                amount: 40_000_000,
+                ..milestone.clone()
+            },
+        ],
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+
+    let invalid_escrows = [
+        (
+            Escrow {
+                engagement_id: String::from_str(&env, ""),
+                ..escrow_properties.clone()
+            },
+            ContractError::EmptyEngagementId,
+        ),
+        (
+            Escrow {
+                amount: 0,
+                ..escrow_properties.clone()
+            },
+            ContractError::AmountCannotBeZero,
+        ),
+        (
+            Escrow {
+                amount: -100_000_000,
+                ..escrow_properties.clone()
+            },
+            ContractError::NegativeAmountNotAllowed,
+        ),
+        (
+            Escrow {
+                service_provider: client_address.clone(),
+                ..escrow_properties.clone()
+            },
+            ContractError::ClientAndServiceProviderMustDiffer,
+        ),
+        (
+            Escrow {
+                platform_fee: 51,
+                ..escrow_properties.clone()
+            },
+            ContractError::PlatformFeeTooHigh,
+        ),
+        (
+            Escrow {
+                platform_fee: -1,
+                ..escrow_properties.clone()
+            },
+            ContractError::PlatformFeeTooHigh,
+        ),
+        (
+            Escrow {
+                milestones: vec![&env],
+                ..escrow_properties.clone()
+            },
+            ContractError::NoMileStoneDefined,
+        ),
+        (
+            Escrow {
+                milestones: vec![&env, milestone.clone()],
+                ..escrow_properties.clone()
+            },
+            ContractError::MilestoneAmountsMustMatchEscrowAmount,
+        ),
+        (
+            Escrow {
+                milestones: vec![
+                    &env,
+                    Milestone {
+                        amount: 110_000_000,
+                        ..milestone.clone()
+                    },
+                    Milestone {
+                        amount: -10_000_000,
+                        ..milestone.clone()
+                    },
+                ],
+                ..escrow_properties.clone()
+            },
+            ContractError::NegativeAmountNotAllowed,
+        ),
+        // Milestones start unapproved, unreleased and undisputed
+        (
+            Escrow {
+                milestones: vec![
+                    &env,
+                    Milestone { flag: true, ..milestone.clone() },
+                    Milestone { amount: 40_000_000, ..milestone.clone() },
+                ],
+                ..escrow_properties.clone()
+            },
+            ContractError::OnlyApproverChangeMilstoneFlag,
+        ),
+        (
+            Escrow {
+                milestones: vec![
+                    &env,
+                    Milestone { released: true, ..milestone.clone() },
+                    Milestone { amount: 40_000_000, ..milestone.clone() },
+                ],
+                ..escrow_properties.clone()
+            },
+            ContractError::MilestoneAlreadyReleased,
+        ),
+        (
+            Escrow {
+                milestones: vec![
+                    &env,
+                    Milestone { dispute_flag: true, ..milestone.clone() },
+                    Milestone { amount: 40_000_000, ..milestone.clone() },
+                ],
+                ..escrow_properties.clone()
+            },
+            ContractError::MilestoneOpenedForDisputeResolution,
+        ),
+    ];
+
+    for (invalid_escrow, expected_error) in invalid_escrows.iter() {
+        let result = engagement_client.try_initialize_escrow(invalid_escrow);
+        assert_eq!(result, Err(Ok(*expected_error)));
+    }
+
+    // The same rules apply when an escrow is amended
+    engagement_client.initialize_escrow(&escrow_properties);
+    let result = engagement_client.try_change_escrow_properties(&Escrow {
+        amount: 90_000_000,
+        ..escrow_properties.clone()
+    });
+    assert_eq!(result, Err(Ok(ContractError::MilestoneAmountsMustMatchEscrowAmount)));
+    let result = engagement_client.try_change_escrow_properties(&Escrow {
+        platform_fee: 60,
+        ..escrow_properties.clone()
+    });
+    assert_eq!(result, Err(Ok(ContractError::PlatformFeeTooHigh)));
+}
+
+#[test]
+fn test_change_escrow_properties() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let release_signer_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+
+    let amount: i128 = 100_000_000;
+    let platform_fee = 3;
+
+    let initial_milestones = vec![
+        &env,
+        Milestone {
+            description: String::from_str(&env, "First milestone"),
+            status: String::from_str(&env, "Pending"),
+            amount: 50_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+        Milestone {
+            description: String::from_str(&env, "Second milestone"),
+            status: String::from_str(&env, "Pending"),
+            amount: 50_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+    ];
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+
+    let engagement_id = String::from_str(&env, "41431");
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee,
+        milestones: initial_milestones.clone(),
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    let initialized_id = engagement_client.initialize_escrow(&escrow_properties);
+
+    // Verify escrow was initialized
+    let initial_escrow = engagement_client.get_escrow_by_id(&initialized_id);
+    assert_eq!(initial_escrow.engagement_id, initialized_id);
+
+    // Create new values for updating the escrow
+    let new_client_address = Address::generate(&env);
+    let new_service_provider = Address::generate(&env);
+    let new_release_signer = Address::generate(&env);
+    let new_dispute_resolver = Address::generate(&env);
+    let new_amount: i128 = 200_000_000;
+    let new_platform_fee = 5;
+
+    let new_milestones = vec![
+        &env,
+        Milestone {
+            description: String::from_str(&env, "Updated first milestone"),
+            status: String::from_str(&env, "Pending"),
+            amount: 100_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+        Milestone {
+            description: String::from_str(&env, "Updated second milestone"),
+            status: String::from_str(&env, "Pending"),
+            amount: 50_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+        Milestone {
+            description: String::from_str(&env, "New third milestone"),
+            status: String::from_str(&env, "Pending"),
+            amount: 50_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+    ];
+
+    // Test unauthorized access (should fail)
+    let unauthorized_address = Address::generate(&env);
+    env.mock_all_auths();
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: new_client_address.clone(),
+        service_provider: new_service_provider.clone(),
+        approver: None,
+        platform_address: unauthorized_address.clone(), // Using unauthorized address
+        asset: usdc_token.address.clone(),
+        amount: new_amount,
+        platform_fee: new_platform_fee,
+        milestones: new_milestones.clone(),
+        release_signers: vec![&env, new_release_signer.clone()],
+        release_threshold: 1,
+        dispute_resolver: new_dispute_resolver.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    let result = engagement_client.try_change_escrow_properties(&escrow_properties);
+    assert!(result.is_err());
+
+    // Update escrow with authorized platform_address
+    env.mock_all_auths();
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: new_client_address.clone(),
+        service_provider: new_service_provider.clone(),
+        approver: None,
+        platform_address: platform_address.clone(), // Using original platform_address
+        asset: usdc_token.address.clone(),
+        amount: new_amount,
+        platform_fee: new_platform_fee,
+        milestones: new_milestones.clone(),
+        release_signers: vec![&env, new_release_signer.clone()],
+        release_threshold: 1,
+        dispute_resolver: new_dispute_resolver.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 24 * 60 * 60,
+    };
+    engagement_client.change_escrow_properties(&escrow_properties);
+
+    // Verify updated escrow properties
+    let updated_escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    assert_eq!(updated_escrow.engagement_id, engagement_id);
+    assert_eq!(updated_escrow.platform_address, platform_address);
+    assert_eq!(updated_escrow.amount, new_amount);
+    assert_eq!(updated_escrow.platform_fee, new_platform_fee);
+    assert_eq!(updated_escrow.milestones, new_milestones);
+    // The client and the service provider cannot be replaced by the platform
+    assert_eq!(updated_escrow.client, client_address);
+    assert_eq!(updated_escrow.service_provider, service_provider_address);
+    assert_eq!(
+        engagement_client.get_escrows_by_role(&new_client_address, &Role::Client, &0, &10).len(),
+        0
+    );
+    // Release signers and the dispute resolver are rotated through their
+    // own entry points, not by the platform
+    assert_eq!(updated_escrow.release_signers, vec![&env, release_signer_address.clone()]);
+    assert_eq!(updated_escrow.dispute_resolver, dispute_resolver_address);
+    // The challenge period is fixed when the escrow is created
+    assert_eq!(updated_escrow.challenge_period, 0);
+    assert_eq!(
+        engagement_client.get_escrows_by_role(&new_release_signer, &Role::ReleaseSigner, &0, &10).len(),
+        0
+    );
+
+    // Test with non-existent escrow (should fail)
+    let non_existent_id = String::from_str(&env, "99999");
+    env.mock_all_auths();
+    let escrow_properties = Escrow {
+        engagement_id: non_existent_id.clone(),
+        client: new_client_address.clone(),
+        service_provider: new_service_provider.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount: new_amount,
+        platform_fee: new_platform_fee,
+        milestones: new_milestones.clone(),
+        release_signers: vec![&env, new_release_signer.clone()],
+        release_threshold: 1,
+        dispute_resolver: new_dispute_resolver.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    let result = engagement_client.try_change_escrow_properties(&escrow_properties);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_amendments_keep_milestone_state() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let release_signer_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+    let trustless_work_address = Address::generate(&env);
+
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    initialize_engagement_contract(&env, &engagement_client, &trustless_work_address, &usdc_token.address);
+
+    let amount: i128 = 100_000_000;
+    let platform_fee = 5;
+    let engagement_id = String::from_str(&env, "amended");
+    let milestone = Milestone {
+        description: String::from_str(&env, "Milestone"),
+        status: String::from_str(&env, "Pending"),
+        amount: 50_000_000,
+        flag: false,
+        released: false,
+        dispute_flag: false,
+    };
+
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee,
+        milestones: vec![&env],
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+
+    engagement_client.initialize_escrow(&Escrow {
+        milestones: vec![
+            &env,
+            milestone.clone(),
+            Milestone { amount: 30_000_000, ..milestone.clone() },
+            Milestone { amount: 20_000_000, ..milestone.clone() },
+        ],
+        ..escrow_properties
+    });
+    usdc_token.mint(&client_address, &amount);
+    engagement_client.fund_escrow(&engagement_id, &client_address, &amount);
+
+    // The first milestone is paid, the second approved and the third disputed
+    engagement_client.change_milestone_flag(&engagement_id, &0, &true, &client_address);
+    engagement_client.distribute_escrow_earnings(
+        &engagement_id,
+        &release_signer_address,
+    );
+    engagement_client.change_milestone_flag(&engagement_id, &1, &true, &client_address);
+    engagement_client.change_dispute_flag(&engagement_id, &2, &client_address);
+
+    let stored_escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    let with_milestone = |index: u32, milestone: Milestone| {
+        let mut milestones = stored_escrow.milestones.clone();
+        milestones.set(index, milestone);
+        Escrow { milestones, ..stored_escrow.clone() }
+    };
+    let released_milestone = stored_escrow.milestones.get(0).unwrap();
+    let approved_milestone = stored_escrow.milestones.get(1).unwrap();
+    let disputed_milestone = stored_escrow.milestones.get(2).unwrap();
+
+    let invalid_amendments = [
+        (
+            with_milestone(0, Milestone { released: false, ..released_milestone.clone() }),
+            ContractError::MilestoneAlreadyReleased,
+        ),
+        (
+            Escrow {
+                amount: 110_000_000,
+                ..with_milestone(0, Milestone { amount: 60_000_000, ..released_milestone.clone() })
+            },
+            ContractError::MilestoneAlreadyReleased,
+        ),
+        (
+            with_milestone(2, Milestone { dispute_flag: false, ..disputed_milestone.clone() }),
+            ContractError::MilestoneOpenedForDisputeResolution,
+        ),
+        (
+            Escrow {
+                amount: 80_000_000,
+                milestones: vec![&env, released_milestone.clone(), approved_milestone.clone()],
+                ..stored_escrow.clone()
+            },
+            ContractError::MilestoneOpenedForDisputeResolution,
+        ),
+        (
+            with_milestone(1, Milestone { flag: false, ..approved_milestone.clone() }),
+            ContractError::OnlyApproverChangeMilstoneFlag,
+        ),
+        (
+            Escrow {
+                amount: 120_000_000,
+                milestones: vec![
+                    &env,
+                    released_milestone.clone(),
+                    approved_milestone.clone(),
+                    disputed_milestone.clone(),
+                    Milestone { amount: 20_000_000, flag: true, ..milestone.clone() },
+                ],
+                ..stored_escrow.clone()
+            },
+            ContractError::OnlyApproverChangeMilstoneFlag,
+        ),
+    ];
+
+    for (invalid_amendment, expected_error) in invalid_amendments.iter() {
+        let result = engagement_client.try_change_escrow_properties(invalid_amendment);
+        assert_eq!(result, Err(Ok(*expected_error)));
+    }
+
+    // Statuses can still be edited, and new terms on an approved milestone
+    // need a new approval
+    let status = String::from_str(&env, "Reviewed");
+    let mut milestones = stored_escrow.milestones.clone();
+    milestones.set(0, Milestone { status: status.clone(), ..released_milestone.clone() });
+    milestones.set(1, Milestone { amount: 40_000_000, ..approved_milestone.clone() });
+    engagement_client.change_escrow_properties(&Escrow {
+        amount: 110_000_000,
+        milestones,
+        ..stored_escrow.clone()
+    });
+
+    let amended_escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    assert_eq!(amended_escrow.milestones.get(0).unwrap(), Milestone { status, ..released_milestone });
+    assert_eq!(
+        amended_escrow.milestones.get(1).unwrap(),
+        Milestone { amount: 40_000_000, flag: false, ..approved_milestone }
+    );
+    assert_eq!(amended_escrow.milestones.get(2).unwrap(), disputed_milestone);
+}
+
+#[test]
+fn test_amendments_of_a_funded_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+
+    let amount: i128 = 100_000_000;
+    usdc_token.mint(&client_address, &amount);
+
+    let milestone = Milestone {
+        description: String::from_str(&env, "Milestone"),
+        status: String::from_str(&env, "Pending"),
+        amount: 50_000_000,
+        flag: false,
+        released: false,
+        dispute_flag: false,
+    };
+    let escrow_properties = Escrow {
+        engagement_id: String::from_str(&env, "funded"),
+        client: client_address.clone(),
+        service_provider: Address::generate(&env),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee: 5,
+        milestones: vec![&env, milestone.clone(), milestone.clone()],
+        release_signers: vec![&env, Address::generate(&env)],
+        release_threshold: 1,
+        dispute_resolver: Address::generate(&env),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    engagement_client.initialize_escrow(&escrow_properties);
+    engagement_client.fund_escrow(&escrow_properties.engagement_id, &client_address, &amount);
+
+    // Deposits cannot end up above the escrow amount
+    let result = engagement_client.try_change_escrow_properties(&Escrow {
+        amount: 80_000_000,
+        milestones: vec![&env, milestone.clone(), Milestone { amount: 30_000_000, ..milestone.clone() }],
+        ..escrow_properties.clone()
+    });
+    assert_eq!(result, Err(Ok(ContractError::AmountToDepositGreatherThanEscrowAmount)));
+
+    let authorizers = |env: &Env| -> std::vec::Vec<Address> {
+        env.auths().into_iter().map(|(address, _)| address).collect()
+    };
+
+    // The platform can still raise the amount on its own
+    let raised_escrow = Escrow {
+        amount: 120_000_000,
+        milestones: vec![&env, milestone.clone(), Milestone { amount: 70_000_000, ..milestone.clone() }],
+        ..escrow_properties.clone()
+    };
+    engagement_client.change_escrow_properties(&raised_escrow);
+    assert_eq!(authorizers(&env), std::vec![platform_address.clone()]);
+
+    // but changing its own fee takes the client's authorization
+    engagement_client.change_escrow_properties(&Escrow { platform_fee: 10, ..raised_escrow });
+    assert_eq!(authorizers(&env), std::vec![platform_address, client_address]);
+    assert_eq!(engagement_client.get_escrow_by_id(&escrow_properties.engagement_id).platform_fee, 10);
+}
+
+#[test]
+fn test_change_milestone_status_and_flag() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let release_signer_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+    let amount: i128 = 100_000_000;
+    let platform_fee = 3;
+
+    let initial_milestones = vec![
+        &env,
+        Milestone {
+            description: String::from_str(&env, "Milestone 1"),
+            status: String::from_str(&env, "in-progress"),
+            amount: 50_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+        Milestone {
+            description: String::from_str(&env, "Milestone 2"),
+            status: String::from_str(&env, "in-progress"),
+            amount: 50_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+    ];
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+
+    let engagement_id = String::from_str(&env, "test_engagement");
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee,
+        milestones: initial_milestones.clone(),
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    engagement_client.initialize_escrow(&escrow_properties);
+
+    // Change milestone status (valid case)
+    let new_status = String::from_str(&env, "completed");
+    engagement_client.change_milestone_status(
+        &engagement_id.clone(),
+        &0_i128, // Milestone index
+        &new_status,
+        &service_provider_address,
+    );
+
+    // Verify milestone status change
+    let updated_escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    assert_eq!(updated_escrow.milestones.get(0).unwrap().status, new_status);
+
+    // Change milestone flag (valid case)
+    engagement_client.change_milestone_flag(&engagement_id, &0_i128, &true, &client_address);
+
+    // Verify milestone flag change
+    let final_escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    assert!(final_escrow.milestones.get(0).unwrap().flag);
+
+    // Invalid index test
+    let invalid_index = 10_i128;
+    let new_status = String::from_str(&env, "completed");
+
+    // Test for `change_status` with invalid index
+    let result = engagement_client.try_change_milestone_status(
+        &engagement_id,
+        &invalid_index,
+        &new_status,
+        &service_provider_address,
+    );
+    assert!(result.is_err());
+
+    // Test for `change_flag` with invalid index
+    let result = engagement_client.try_change_milestone_flag(
+        &engagement_id,
+        &invalid_index,
+        &true,
+        &client_address,
+    );
+    assert!(result.is_err());
+
+    // Invalid Engagement ID test
+    let invalid_engagement_id = String::from_str(&env, "invalid_engagement");
+
+    // Test for `change_status` with invalid engagement ID
+    let result = engagement_client.try_change_milestone_status(
+        &invalid_engagement_id,
+        &0_i128,
+        &new_status,
+        &service_provider_address,
+    );
+    assert!(result.is_err());
+
+    // Test for `change_flag` with invalid engagement ID
+    let result = engagement_client.try_change_milestone_flag(
+        &invalid_engagement_id,
+        &0_i128,
+        &true,
+        &client_address,
+    );
+    assert!(result.is_err());
+
+    // Test only authorized party can perform the function
+    let unauthorized_address = Address::generate(&env);
+
+    // Test for `change_status` by invalid service provider
+    let result = engagement_client.try_change_milestone_status(
+        &engagement_id,
+        &0_i128,
+        &new_status,
+        &unauthorized_address,
+    );
+    assert!(result.is_err());
+
+    // Test for `change_flag` by invalid client
+    let result = engagement_client.try_change_milestone_flag(
+        &engagement_id,
+        &0_i128,
+        &true,
+        &unauthorized_address,
+    );
+    assert!(result.is_err());
+
+    // An escrow cannot be amended to have no milestones
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee,
+        milestones: vec![&env],
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    let result = engagement_client.try_change_escrow_properties(&escrow_properties);
+    assert_eq!(result, Err(Ok(ContractError::NoMileStoneDefined)));
+}
+
+#[test]
+fn test_approver_role() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let release_signer_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+    let approver_address = Address::generate(&env);
+    let amount: i128 = 100_000_000;
+    let platform_fee = 3;
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            description: String::from_str(&env, "Milestone 1"),
+            status: String::from_str(&env, "in-progress"),
+            amount: 50_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+        Milestone {
+            description: String::from_str(&env, "Milestone 2"),
+            status: String::from_str(&env, "in-progress"),
+            amount: 50_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+    ];
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+
+    let engagement_id = String::from_str(&env, "test_approver");
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: Some(approver_address.clone()),
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee,
+        milestones: milestones.clone(),
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    engagement_client.initialize_escrow(&escrow_properties);
+
+    let escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    assert_eq!(escrow.approver, Some(approver_address.clone()));
+
+    // The client no longer approves milestones once an approver is set
+    let result = engagement_client.try_change_milestone_flag(
+        &engagement_id,
+        &0_i128,
+        &true,
+        &client_address,
+    );
+    assert!(result.is_err());
+
+    engagement_client.change_milestone_flag(&engagement_id, &0_i128, &true, &approver_address);
+    let escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    assert!(escrow.milestones.get(0).unwrap().flag);
+
+    // Only the client can replace the approver
+    let new_approver_address = Address::generate(&env);
+    let result = engagement_client.try_update_approver(
+        &engagement_id,
+        &approver_address,
+        &new_approver_address,
+    );
+    assert!(result.is_err());
+
+    engagement_client.update_approver(&engagement_id, &client_address, &new_approver_address);
+    let escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    assert_eq!(escrow.approver, Some(new_approver_address.clone()));
+
+    let result = engagement_client.try_change_milestone_flag(
+        &engagement_id,
+        &1_i128,
+        &true,
+        &approver_address,
+    );
+    assert!(result.is_err());
+
+    engagement_client.change_milestone_flag(&engagement_id, &1_i128, &true, &new_approver_address);
+    let escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    assert!(escrow.milestones.get(1).unwrap().flag);
+}
+
+#[test]
+fn test_role_rotation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let release_signer_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+    let amount: i128 = 100_000_000;
+    let platform_fee = 3;
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            description: String::from_str(&env, "First milestone"),
+            status: String::from_str(&env, "Pending"),
+            amount: 100_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+    ];
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+
+    let engagement_id = String::from_str(&env, "test_role_rotation");
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee,
+        milestones: milestones.clone(),
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    engagement_client.initialize_escrow(&escrow_properties);
+
+    // Rotating the release signer needs the current signer, the client and the service provider
+    let new_release_signer = Address::generate(&env);
+    engagement_client.update_release_signer(&engagement_id, &release_signer_address, &new_release_signer);
+
+    let authorized: std::vec::Vec<Address> = env.auths().into_iter().map(|(address, _)| address).collect();
+    assert!(authorized.contains(&release_signer_address));
+    assert!(authorized.contains(&client_address));
+    assert!(authorized.contains(&service_provider_address));
+
+    let escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    assert_eq!(escrow.release_signers, vec![&env, new_release_signer.clone()]);
+
+    // Rotating the dispute resolver needs the current resolver, the client and the service provider
+    let new_dispute_resolver = Address::generate(&env);
+    engagement_client.update_dispute_resolver(&engagement_id, &new_dispute_resolver);
+
+    let authorized: std::vec::Vec<Address> = env.auths().into_iter().map(|(address, _)| address).collect();
+    assert!(authorized.contains(&dispute_resolver_address));
+    assert!(authorized.contains(&client_address));
+    assert!(authorized.contains(&service_provider_address));
+
+    let escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    assert_eq!(escrow.dispute_resolver, new_dispute_resolver);
+
+    // Roles cannot be rotated while a dispute is open
+    engagement_client.change_dispute_flag(&engagement_id, &0_i128, &client_address);
+
+    let result = engagement_client.try_update_release_signer(&engagement_id, &new_release_signer, &release_signer_address);
+    assert!(result.is_err());
+
+    let result = engagement_client.try_update_dispute_resolver(&engagement_id, &dispute_resolver_address);
+    assert!(result.is_err());
+
+    let escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    assert_eq!(escrow.release_signers, vec![&env, new_release_signer.clone()]);
+    assert_eq!(escrow.dispute_resolver, new_dispute_resolver);
+}
+
+#[test]
+fn test_get_escrows_by_participant() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client_address = Address::generate(&env);
+    let other_client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let release_signer_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+    let new_dispute_resolver_address = Address::generate(&env);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            description: String::from_str(&env, "First milestone"),
+            status: String::from_str(&env, "Pending"),
+            amount: 100_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+    ];
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+
+    let first_id = String::from_str(&env, "engagement_1");
+    let second_id = String::from_str(&env, "engagement_2");
+    let third_id = String::from_str(&env, "engagement_3");
+
+    let escrow_properties = Escrow {
+        engagement_id: first_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount: 100_000_000,
+        platform_fee: 5,
+        milestones: milestones.clone(),
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    engagement_client.initialize_escrow(&escrow_properties);
+    engagement_client.initialize_escrow(&Escrow {
+        engagement_id: second_id.clone(),
+        ..escrow_properties.clone()
+    });
+    engagement_client.initialize_escrow(&Escrow {
+        engagement_id: third_id.clone(),
+        client: other_client_address.clone(),
+        ..escrow_properties.clone()
+    });
+
+    // Engagement ids are unique per contract
+    let result = engagement_client.try_initialize_escrow(&escrow_properties);
+    assert_eq!(result, Err(Ok(ContractError::EscrowAlreadyInitialized)));
+
+    assert_eq!(
+        engagement_client.get_escrows_by_client(&client_address, &0, &10),
+        vec![&env, first_id.clone(), second_id.clone()]
+    );
+    assert_eq!(
+        engagement_client.get_escrows_by_client(&other_client_address, &0, &10),
+        vec![&env, third_id.clone()]
+    );
+    assert_eq!(
+        engagement_client.get_escrows_by_service_provider(&service_provider_address, &0, &10),
+        vec![&env, first_id.clone(), second_id.clone(), third_id.clone()]
+    );
+
+    // Pagination
+    assert_eq!(
+        engagement_client.get_escrows_by_platform(&platform_address, &1, &1),
+        vec![&env, second_id.clone()]
+    );
+    assert_eq!(
+        engagement_client.get_escrows_by_platform(&platform_address, &2, &10),
+        vec![&env, third_id.clone()]
+    );
+    assert_eq!(
+        engagement_client.get_escrows_by_platform(&platform_address, &3, &10),
+        vec![&env]
+    );
+
+    // Without an approver the client is indexed as the approver
+    assert_eq!(
+        engagement_client.get_escrows_by_role(&client_address, &Role::Approver, &0, &10),
+        vec![&env, first_id.clone(), second_id.clone()]
+    );
+    assert_eq!(
+        engagement_client.get_escrows_by_role(&release_signer_address, &Role::ReleaseSigner, &0, &10),
+        vec![&env, first_id.clone(), second_id.clone(), third_id.clone()]
+    );
+
+    // Amendments move the escrow to the new participant; the last escrow of
+    // the previous participant takes its position
+    engagement_client.update_dispute_resolver(&first_id, &new_dispute_resolver_address);
+    assert_eq!(
+        engagement_client.get_escrows_by_role(&dispute_resolver_address, &Role::DisputeResolver, &0, &10),
+        vec![&env, third_id.clone(), second_id.clone()]
+    );
+    assert_eq!(
+        engagement_client.get_escrows_by_role(&new_dispute_resolver_address, &Role::DisputeResolver, &0, &10),
+        vec![&env, first_id.clone()]
+    );
+    // Roles that did not change keep their order
+    assert_eq!(
+        engagement_client.get_escrows_by_client(&client_address, &0, &10),
+        vec![&env, first_id.clone(), second_id.clone()]
+    );
+
+    engagement_client.update_approver(&second_id, &client_address, &other_client_address);
+    assert_eq!(
+        engagement_client.get_escrows_by_role(&client_address, &Role::Approver, &0, &10),
+        vec![&env, first_id.clone()]
+    );
+    assert_eq!(
+        engagement_client.get_escrows_by_role(&other_client_address, &Role::Approver, &0, &10),
+        vec![&env, third_id.clone(), second_id.clone()]
+    );
+}
+
+#[test]
+fn test_list_escrows_with_status_filter() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let release_signer_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+    let trustless_work_address = Address::generate(&env);
+
+    let usdc_token = create_usdc_token(&env, &admin);
+    let amount: i128 = 100_000_000;
+    usdc_token.mint(&client_address, &(amount * 2));
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            description: String::from_str(&env, "First milestone"),
+            status: String::from_str(&env, "Pending"),
+            amount,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+    ];
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    initialize_engagement_contract(&env, &engagement_client, &trustless_work_address, &usdc_token.address);
+
+    let initialized_id = String::from_str(&env, "initialized");
+    let funded_id = String::from_str(&env, "funded");
+    let disputed_id = String::from_str(&env, "disputed");
+    let completed_id = String::from_str(&env, "completed");
+
+    let escrow_properties = Escrow {
+        engagement_id: initialized_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee: 5,
+        milestones: milestones.clone(),
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    for engagement_id in [&initialized_id, &funded_id, &disputed_id, &completed_id] {
+        engagement_client.initialize_escrow(&Escrow {
+            engagement_id: engagement_id.clone(),
+            ..escrow_properties.clone()
+        });
+    }
+
+    engagement_client.fund_escrow(&funded_id, &client_address, &amount);
+    engagement_client.change_dispute_flag(&disputed_id, &0_i128, &client_address);
+    engagement_client.fund_escrow(&completed_id, &client_address, &amount);
+    engagement_client.change_milestone_flag(&completed_id, &0_i128, &true, &client_address);
+    engagement_client.distribute_escrow_earnings(
+        &completed_id,
+        &release_signer_address,
+    );
+
+    let all_escrows = engagement_client.list_escrows(&0, &10, &None);
+    assert_eq!(all_escrows.escrows.len(), 4);
+    assert_eq!(all_escrows.escrows.get(0).unwrap().engagement_id, initialized_id);
+    assert_eq!(all_escrows.escrows.get(3).unwrap().engagement_id, completed_id);
+    assert_eq!(all_escrows.next_start, None);
+
+    let page = engagement_client.list_escrows(&1, &2, &None);
+    assert_eq!(page.escrows.len(), 2);
+    assert_eq!(page.escrows.get(0).unwrap().engagement_id, funded_id);
+    assert_eq!(page.escrows.get(1).unwrap().engagement_id, disputed_id);
+    assert_eq!(page.next_start, Some(3));
+
+    assert_eq!(engagement_client.list_escrows(&4, &10, &None).escrows.len(), 0);
+
+    for (status, engagement_id) in [
+        (EscrowStatus::Initialized, &initialized_id),
+        (EscrowStatus::Funded, &funded_id),
+        (EscrowStatus::InDispute, &disputed_id),
+        (EscrowStatus::Completed, &completed_id),
+    ] {
+        let filtered = engagement_client.list_escrows(&0, &10, &Some(status));
+        assert_eq!(filtered.escrows.len(), 1);
+        assert_eq!(filtered.escrows.get(0).unwrap().engagement_id, engagement_id.clone());
+        assert_eq!(engagement_client.get_escrow_status(engagement_id), status);
+    }
+
+    // `start` is a position in creation order, with or without a filter
+    assert_eq!(
+        engagement_client.list_escrows(&2, &10, &Some(EscrowStatus::Funded)).escrows.len(),
+        0
+    );
+}
+
+#[test]
+fn test_list_escrows_scans_a_bounded_number_of_escrows() {
+    // The snapshot of this many escrows is too large to keep
+    let env = Env::new_with_config(EnvTestConfig { capture_snapshot_at_drop: false });
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client_address = Address::generate(&env);
+
+    let usdc_token = create_usdc_token(&env, &admin);
+    let amount: i128 = 100_000_000;
+    usdc_token.mint(&client_address, &amount);
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+
+    let escrow_properties = Escrow {
+        engagement_id: String::from_str(&env, "funded"),
+        client: client_address.clone(),
+        service_provider: Address::generate(&env),
+        approver: None,
+        platform_address: Address::generate(&env),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee: 5,
+        milestones: vec![
+            &env,
+            Milestone {
+                description: String::from_str(&env, "First milestone"),
+                status: String::from_str(&env, "Pending"),
+                amount,
+                flag: false,
+                released: false,
+                dispute_flag: false,
+            },
+        ],
+        release_signers: vec![&env, Address::generate(&env)],
+        release_threshold: 1,
+        dispute_resolver: Address::generate(&env),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    engagement_client.initialize_escrow(&escrow_properties);
+    engagement_client.fund_escrow(&escrow_properties.engagement_id, &client_address, &amount);
+
+    env.budget().reset_unlimited();
+    for index in 0..MAX_ESCROWS_SCANNED {
+        engagement_client.initialize_escrow(&Escrow {
+            engagement_id: String::from_str(&env, &std::format!("pending_{}", index)),
+            ..escrow_properties.clone()
+        });
+    }
+
+    // A filtered call stops after a bounded scan, which fits in the default
+    // budget, and tells where to resume
+    env.budget().reset_default();
+    let page = engagement_client.list_escrows(&0, &10, &Some(EscrowStatus::Funded));
+    assert_eq!(page.escrows.len(), 1);
+    assert_eq!(page.escrows.get(0).unwrap().engagement_id, escrow_properties.engagement_id);
+    assert_eq!(page.next_start, Some(MAX_ESCROWS_SCANNED));
+
+    let page = engagement_client.list_escrows(&MAX_ESCROWS_SCANNED, &10, &Some(EscrowStatus::Funded));
+    assert_eq!(page.escrows.len(), 0);
+    assert_eq!(page.next_start, None);
+
+    // Role lookups are bounded the same way
+    let escrows = engagement_client.get_escrows_by_client(&client_address, &0, &u32::MAX);
+    assert_eq!(escrows.len(), MAX_ESCROWS_SCANNED);
+    assert_eq!(
+        engagement_client.get_escrows_by_client(&client_address, &MAX_ESCROWS_SCANNED, &u32::MAX).len(),
+        1
+    );
+}
+
+#[test]
+fn test_escrow_storage_ttl_is_extended() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let release_signer_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+
+    let engagement_id = String::from_str(&env, "long_running");
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount: 100_000_000,
+        platform_fee: 5,
+        milestones: vec![
+            &env,
+            Milestone {
+                description: String::from_str(&env, "First milestone"),
+                status: String::from_str(&env, "Pending"),
+                amount: 100_000_000,
+                flag: false,
+                released: false,
+                dispute_flag: false,
+            },
+        ],
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    engagement_client.initialize_escrow(&escrow_properties);
+
+    let escrow_ttl = || {
+        env.as_contract(&engagement_contract_address, || {
+            env.storage().persistent().get_ttl(&DataKey::Escrow(engagement_id.clone()))
+        })
+    };
+    assert_eq!(escrow_ttl(), PERSISTENT_BUMP_AMOUNT);
+
+    // Once the remaining TTL drops below the threshold a bump restores it
+    env.ledger().with_mut(|li| li.sequence_number += 2 * DAY_IN_LEDGERS);
+    assert_eq!(escrow_ttl(), PERSISTENT_BUMP_AMOUNT - 2 * DAY_IN_LEDGERS);
+
+    engagement_client.bump_escrow(&engagement_id);
+    assert_eq!(escrow_ttl(), PERSISTENT_BUMP_AMOUNT);
+
+    // The index entries that point at the escrow are extended with it
+    env.as_contract(&engagement_contract_address, || {
+        for key in [
+            DataKey::EscrowIndex(0),
+            DataKey::EscrowsByRole(Role::Client, client_address.clone(), 0),
+            DataKey::EscrowsByRoleCount(Role::ReleaseSigner, release_signer_address.clone()),
+        ] {
+            assert_eq!(env.storage().persistent().get_ttl(&key), PERSISTENT_BUMP_AMOUNT);
+        }
+    });
+
+    // Any interaction with the escrow extends it as well
+    env.ledger().with_mut(|li| li.sequence_number += 2 * DAY_IN_LEDGERS);
+    engagement_client.change_milestone_status(
+        &engagement_id,
+        &0_i128,
+        &String::from_str(&env, "In progress"),
+        &service_provider_address,
+    );
+    assert_eq!(escrow_ttl(), PERSISTENT_BUMP_AMOUNT);
+    // and the contract instance the escrow depends on
+    let instance_ttl = env.as_contract(&engagement_contract_address, || env.storage().instance().get_ttl());
+    assert_eq!(instance_ttl, INSTANCE_BUMP_AMOUNT);
+
+    let result = engagement_client.try_bump_escrow(&String::from_str(&env, "unknown"));
+    assert_eq!(result, Err(Ok(ContractError::EscrowNotFound)));
+}
+
+
+#[test]
+fn test_escrow_events_are_emitted_once_per_change() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let release_signer_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            description: String::from_str(&env, "First milestone"),
+            status: String::from_str(&env, "Pending"),
+            amount: 100_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+    ];
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+
+    let engagement_id = String::from_str(&env, "test_events");
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount: 100_000_000,
+        platform_fee: 3,
+        milestones,
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    let events_before_initialize = env.events().all();
+    engagement_client.initialize_escrow(&escrow_properties);
+    let events_after_initialize = env.events().all();
+    assert_eq!(events_after_initialize.len(), events_before_initialize.len() + 1);
+
+    // Reading an escrow does not publish anything
+    engagement_client.get_escrow_by_id(&engagement_id);
+    assert_eq!(env.events().all(), events_after_initialize);
+
+    let (_, topics, data) = events_after_initialize.last().unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&env, "escrow_created"),
+            engagement_id.clone(),
+            client_address.clone(),
+            service_provider_address.clone(),
+        ).into_val(&env)
+    );
+    let published_escrow: Escrow = data.into_val(&env);
+    assert_eq!(published_escrow, escrow_properties);
+
+    // A state change publishes a single event describing the change
+    let new_status = String::from_str(&env, "Completed");
+    engagement_client.change_milestone_status(
+        &engagement_id,
+        &0_i128,
+        &new_status,
+        &service_provider_address,
+    );
+
+    let events = env.events().all();
+    assert_eq!(events.len(), events_after_initialize.len() + 1);
+
+    let (_, topics, data) = events.last().unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&env, "milestone_status_changed"),
+            engagement_id.clone(),
+            service_provider_address.clone(),
+        ).into_val(&env)
+    );
+    let payload: MilestoneStatusChangedEvent = data.into_val(&env);
+    assert_eq!(payload.milestone_index, 0);
+    assert_eq!(payload.status, new_status);
+
+    // Opening a dispute is published with the resolver in the topics
+    engagement_client.change_dispute_flag(&engagement_id, &0_i128, &client_address);
+
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&env, "dispute_opened"),
+            engagement_id.clone(),
+            dispute_resolver_address.clone(),
+        ).into_val(&env)
+    );
+    let payload: DisputeOpenedEvent = data.into_val(&env);
+    assert_eq!(payload.milestone_index, 0);
+    assert_eq!(payload.amount, 100_000_000);
+}
+
+#[test]
+fn test_distribute_escrow_earnings_successful_flow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let release_signer_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+    let trustless_work_address = Address::generate(&env);
+
+    let usdc_token = create_usdc_token(&env, &admin);
+
+    let amount: i128 = 100_000_000;
+    usdc_token.mint(&client_address, &amount);
+
+    let platform_fee = 5;
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            description: String::from_str(&env, "First milestone"),
+            status: String::from_str(&env, "Completed"),
+            amount: 50_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+        Milestone {
+            description: String::from_str(&env, "Second milestone"),
+            status: String::from_str(&env, "Completed"),
+            amount: 50_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+    ];
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    initialize_engagement_contract(&env, &engagement_client, &trustless_work_address, &usdc_token.address);
+
+    let engagement_id = String::from_str(&env, "test_escrow_1");
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee,
+        milestones: milestones.clone(),
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    engagement_client.initialize_escrow(&escrow_properties);
+    engagement_client.change_milestone_flag(&engagement_id, &0, &true, &client_address);
+    engagement_client.change_milestone_flag(&engagement_id, &1, &true, &client_address);
+
+    engagement_client.fund_escrow(&engagement_id, &client_address, &amount);
+
+    // A protocol fee raised after creation cannot leave the service provider
+    // with a negative amount
+    let protocol_config = engagement_client.get_protocol_config();
+    engagement_client.set_protocol_config(&ProtocolConfig {
+        trustless_work_fee: 9_900,
+        max_platform_fee: 1,
+        ..protocol_config.clone()
+    });
+    assert_eq!(
+        engagement_client.try_distribute_escrow_earnings(&engagement_id, &release_signer_address),
+        Err(Ok(ContractError::PlatformFeeTooHigh))
+    );
+    engagement_client.set_protocol_config(&protocol_config);
+
+    engagement_client.distribute_escrow_earnings(
+        &engagement_id,
+        &release_signer_address,
+    );
+
+    let total_amount = amount;
+    let trustless_work_commission = (total_amount * 30) / 10000;
+    let platform_commission = (total_amount * platform_fee) / 100;
+    let service_provider_amount =
+        total_amount - (trustless_work_commission + platform_commission);
+
+    assert_eq!(
+        usdc_token.balance(&trustless_work_address),
+        trustless_work_commission,
+        "Trustless Work commission amount is incorrect"
+    );
+
+    assert_eq!(
+        usdc_token.balance(&platform_address),
+        platform_commission,
+        "Platform commission amount is incorrect"
+    );
+
+    assert_eq!(
+        usdc_token.balance(&service_provider_address),
+        service_provider_amount,
+        "Service Provider received incorrect amount"
+    );
+
+    assert_eq!(
+        usdc_token.balance(&engagement_contract_address),
+        0,
+        "Contract should have zero balance after claiming earnings"
+    );
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&env, "funds_released"),
+            engagement_id.clone(),
+            service_provider_address.clone(),
+            platform_address.clone(),
+        ).into_val(&env)
+    );
+    let payload: FundsReleasedEvent = data.into_val(&env);
+    assert_eq!(payload.amount, total_amount);
+    assert_eq!(payload.trustless_work_commission, trustless_work_commission);
+    assert_eq!(payload.platform_commission, platform_commission);
+    assert_eq!(payload.service_provider_amount, service_provider_amount);
+}
+
+#[test]
+fn test_distribute_escrow_earnings_multisig_threshold() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let first_signer = Address::generate(&env);
+    let second_signer = Address::generate(&env);
+    let third_signer = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+    let trustless_work_address = Address::generate(&env);
+
+    let usdc_token = create_usdc_token(&env, &admin);
+
+    let amount: i128 = 100_000_000;
+    let platform_fee = 5;
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            description: String::from_str(&env, "First milestone"),
+            status: String::from_str(&env, "Completed"),
+            amount: 100_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+    ];
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    initialize_engagement_contract(&env, &engagement_client, &trustless_work_address, &usdc_token.address);
+
+    let engagement_id = String::from_str(&env, "test_multisig");
+
+    // The threshold cannot exceed the number of signers
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee,
+        milestones: milestones.clone(),
+        release_signers: vec![&env, first_signer.clone(), second_signer.clone(), third_signer.clone()],
+        release_threshold: 4,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    let result = engagement_client.try_initialize_escrow(&escrow_properties);
+    assert!(result.is_err());
+
+    let escrow_properties = Escrow {
+        release_threshold: 2,
+        ..escrow_properties
+    };
+    engagement_client.initialize_escrow(&escrow_properties);
+    engagement_client.change_milestone_flag(&engagement_id, &0, &true, &client_address);
+
+    usdc_token.mint(&client_address, &amount);
+    engagement_client.fund_escrow(&engagement_id, &client_address, &amount);
+
+    // The first approval is recorded but funds stay in the escrow
+    engagement_client.distribute_escrow_earnings(
+        &engagement_id,
+        &first_signer,
+    );
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "release_approved"), engagement_id.clone(), first_signer.clone()).into_val(&env)
+    );
+    let approvals: ReleaseApprovals = data.into_val(&env);
+    assert_eq!(
+        approvals,
+        ReleaseApprovals { milestone_indexes: vec![&env, 0], signers: vec![&env, first_signer.clone()] }
+    );
+    assert_eq!(usdc_token.balance(&engagement_contract_address), amount);
+    assert_eq!(
+        engagement_client.get_release_approvals(&engagement_id),
+        vec![&env, first_signer.clone()]
+    );
+
+    // A signer cannot approve twice
+    let result = engagement_client.try_distribute_escrow_earnings(
+        &engagement_id,
+        &first_signer,
+    );
+    assert!(result.is_err());
+
+    // Addresses outside the signer list cannot approve
+    let unauthorized_address = Address::generate(&env);
+    let result = engagement_client.try_distribute_escrow_earnings(
+        &engagement_id,
+        &unauthorized_address,
+    );
+    assert!(result.is_err());
+
+    // Approvals are given for the approved milestones and start over when those change
+    engagement_client.change_milestone_flag(&engagement_id, &0, &false, &client_address);
+    engagement_client.change_milestone_flag(&engagement_id, &0, &true, &client_address);
+    assert_eq!(engagement_client.get_release_approvals(&engagement_id).len(), 0);
+
+    engagement_client.distribute_escrow_earnings(
+        &engagement_id,
+        &second_signer,
+    );
+    assert_eq!(usdc_token.balance(&engagement_contract_address), amount);
+
+    // The approval that reaches the threshold moves the funds
+    engagement_client.distribute_escrow_earnings(
+        &engagement_id,
+        &third_signer,
+    );
+
+    let trustless_work_commission = (amount * 30) / 10000;
+    let platform_commission = (amount * platform_fee) / 100;
+    let service_provider_amount = amount - trustless_work_commission - platform_commission;
+
+    assert_eq!(usdc_token.balance(&service_provider_address), service_provider_amount);
+    assert_eq!(usdc_token.balance(&engagement_contract_address), 0);
+    assert_eq!(engagement_client.get_release_approvals(&engagement_id).len(), 0);
+}
+
+#[test]
+fn test_release_waits_out_the_challenge_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let release_signer_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+    let trustless_work_address = Address::generate(&env);
+
+    let usdc_token = create_usdc_token(&env, &admin);
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    initialize_engagement_contract(&env, &engagement_client, &trustless_work_address, &usdc_token.address);
+
+    let engagement_id = String::from_str(&env, "challenged_release");
+    let amount: i128 = 100_000_000;
+    let platform_fee = 5;
+    let challenge_period: u64 = 3 * 24 * 60 * 60;
+
+    let milestone = Milestone {
+        description: String::from_str(&env, "First milestone"),
+        status: String::from_str(&env, "Completed"),
+        amount: 60_000_000,
+        flag: false,
+        released: false,
+        dispute_flag: false,
+    };
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee,
+        milestones: vec![
+            &env,
+            milestone.clone(),
+            Milestone {
+                description: String::from_str(&env, "Second milestone"),
+                amount: 40_000_000,
+                ..milestone
+            },
+        ],
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period,
+    };
+
+    // A release cannot be held back indefinitely
+    let result = engagement_client.try_initialize_escrow(&Escrow {
+        challenge_period: MAX_CHALLENGE_PERIOD + 1,
+        ..escrow_properties.clone()
+    });
+    assert_eq!(result, Err(Ok(ContractError::InvalidEscrowSchedule)));
+
+    engagement_client.initialize_escrow(&escrow_properties);
+    engagement_client.change_milestone_flag(&engagement_id, &0, &true, &client_address);
+    engagement_client.change_milestone_flag(&engagement_id, &1, &true, &client_address);
+
+    usdc_token.mint(&client_address, &amount);
+    engagement_client.fund_escrow(&engagement_id, &client_address, &amount);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    // Reaching the threshold starts the challenge period instead of paying out
+    engagement_client.request_release(&engagement_id, &release_signer_address);
+    assert_eq!(usdc_token.balance(&engagement_contract_address), amount);
+
+    let pending_release = PendingRelease {
+        milestone_indexes: vec![&env, 0, 1],
+        executable_at: 1_000 + challenge_period,
+    };
+    assert_eq!(engagement_client.get_pending_release(&engagement_id), Some(pending_release.clone()));
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "release_requested"), engagement_id.clone(), release_signer_address.clone()).into_val(&env)
+    );
+    let requested: PendingRelease = data.into_val(&env);
+    assert_eq!(requested, pending_release);
+
+    assert_eq!(
+        engagement_client.try_request_release(&engagement_id, &release_signer_address),
+        Err(Ok(ContractError::ReleaseAlreadyRequested))
+    );
+    assert_eq!(
+        engagement_client.try_execute_release(&engagement_id),
+        Err(Ok(ContractError::ReleaseNotYetDue))
+    );
+
+    // The client disputes the second milestone during the challenge period
+    engagement_client.change_dispute_flag(&engagement_id, &1, &client_address);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000 + challenge_period);
+    engagement_client.execute_release(&engagement_id);
+
+    let released_amount: i128 = 60_000_000;
+    let trustless_work_commission = (released_amount * 30) / 10000;
+    let platform_commission = (released_amount * platform_fee) / 100;
+    let service_provider_amount = released_amount - trustless_work_commission - platform_commission;
+
+    assert_eq!(usdc_token.balance(&service_provider_address), service_provider_amount);
+    assert_eq!(usdc_token.balance(&engagement_contract_address), amount - released_amount);
+
+    let escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    assert!(escrow.milestones.get(0).unwrap().released);
+    assert!(!escrow.milestones.get(1).unwrap().released);
+    assert_eq!(engagement_client.get_pending_release(&engagement_id), None);
+    assert_eq!(
+        engagement_client.try_execute_release(&engagement_id),
+        Err(Ok(ContractError::NothingToRelease))
+    );
+}
+
+//test claim escrow earnings in failure scenarios
+// Scenario 1: Escrow with no milestones:
+#[test]
+fn test_distribute_escrow_earnings_no_milestones() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let release_signer_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+
+    let usdc_token = create_usdc_token(&env, &admin);
+
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
+
+    let engagement_id_no_milestones = String::from_str(&env, "test_no_milestones");
+    let amount: i128 = 100_000_000;
+    let platform_fee = 3;
+
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id_no_milestones.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee,
+        milestones: vec![&env], // Empty milestones
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+
+    // An escrow without milestones is rejected up front
+    let result = engagement_client.try_initialize_escrow(&escrow_properties);
+    assert_eq!(result, Err(Ok(ContractError::NoMileStoneDefined)));
+
+    let result = engagement_client.try_distribute_escrow_earnings(
+        &engagement_id_no_milestones,
+        &release_signer_address,
+    );
+    assert_eq!(result, Err(Ok(ContractError::EscrowNotFound)));
+}
+
+// Scenario 2: Milestones incomplete
+#[test]
+fn test_distribute_escrow_earnings_milestones_incomplete() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let service_provider_address = Address::generate(&env);
+    let platform_address = Address::generate(&env);
+    let release_signer_address = Address::generate(&env);
+    let dispute_resolver_address = Address::generate(&env);
+
+    let usdc_token = create_usdc_token(&env, &admin);
 
     let engagement_contract_address = env.register_contract(None, EngagementContract);
     let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
 
-    let engagement_id = String::from_str(&env, "41431");
-    let initialized_id = engagement_client.initialize_escrow(
-        &engagement_id.clone(),
-        &client_address,
-        &service_provider_address,
-        &platform_address,
-        &amount,
-        &platform_fee,
-        &initial_milestones,
-        &release_signer_address,
-        &dispute_resolver_address,
-    );
-
-    // Verify escrow was initialized
-    let initial_escrow = engagement_client.get_escrow_by_id(&initialized_id);
-    assert_eq!(initial_escrow.engagement_id, initialized_id);
-
-    // Create new values for updating the escrow
-    let new_client_address = Address::generate(&env);
-    let new_service_provider = Address::generate(&env);
-    let new_release_signer = Address::generate(&env);
-    let new_dispute_resolver = Address::generate(&env);
-    let new_amount: i128 = 200_000_000;
-    let new_platform_fee = (0.5 * 10i128.pow(18) as f64) as i128;
-
-    let new_milestones = vec![
+    let engagement_id_incomplete = String::from_str(&env, "test_incomplete_milestones");
+    let milestones_incomplete = vec![
         &env,
         Milestone {
-            description: String::from_str(&env, "Updated first milestone"),
-            status: String::from_str(&env, "Pending"),
-            flag: false,
-        },
-        Milestone {
-            description: String::from_str(&env, "Updated second milestone"),
-            status: String::from_str(&env, "Pending"),
-            flag: false,
-        },
-        Milestone {
-            description: String::from_str(&env, "New third milestone"),
+            description: String::from_str(&env, "Incomplete milestone"),
             status: String::from_str(&env, "Pending"),
+            amount: 100_000_000,
             flag: false,
+            released: false,
+            dispute_flag: false,
         },
     ];
 
-    // Test unauthorized access (should fail)
-    let unauthorized_address = Address::generate(&env);
-    env.mock_all_auths();
-    let result = engagement_client.try_change_escrow_properties(
-        &engagement_id,
-        &new_client_address,
-        &new_service_provider,
-        &unauthorized_address, // Using unauthorized address
-        &new_amount,
-        &new_platform_fee,
-        &new_milestones,
-        &new_release_signer,
-        &new_dispute_resolver,
-    );
-    assert!(result.is_err());
-
-    // Update escrow with authorized platform_address
-    env.mock_all_auths();
-    engagement_client.change_escrow_properties(
-        &engagement_id,
-        &new_client_address,
-        &new_service_provider,
-        &platform_address, // Using original platform_address
-        &new_amount,
-        &new_platform_fee,
-        &new_milestones,
-        &new_release_signer,
-        &new_dispute_resolver,
-    );
+    let amount: i128 = 100_000_000;
+    let platform_fee = 3;
 
-    // Verify updated escrow properties
-    let updated_escrow = engagement_client.get_escrow_by_id(&engagement_id);
-    assert_eq!(updated_escrow.engagement_id, engagement_id);
-    assert_eq!(updated_escrow.client, new_client_address);
-    assert_eq!(updated_escrow.service_provider, new_service_provider);
-    assert_eq!(updated_escrow.platform_address, platform_address);
-    assert_eq!(updated_escrow.amount, new_amount);
-    assert_eq!(updated_escrow.platform_fee, new_platform_fee);
-    assert_eq!(updated_escrow.milestones, new_milestones);
-    assert_eq!(updated_escrow.release_signer, new_release_signer);
-    assert_eq!(updated_escrow.dispute_resolver, new_dispute_resolver);
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id_incomplete.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee,
+        milestones: milestones_incomplete.clone(),
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    engagement_client.initialize_escrow(&escrow_properties);
 
-    // Test with non-existent escrow (should fail)
-    let non_existent_id = String::from_str(&env, "99999");
-    env.mock_all_auths();
-    let result = engagement_client.try_change_escrow_properties(
-        &non_existent_id,
-        &new_client_address,
-        &new_service_provider,
-        &platform_address,
-        &new_amount,
-        &new_platform_fee,
-        &new_milestones,
-        &new_release_signer,
-        &new_dispute_resolver,
+    // Try to claim earnings with incomplete milestones (should fail)
+    let result = engagement_client.try_distribute_escrow_earnings(
+        &engagement_id_incomplete,
+        &release_signer_address,
+    );
+    assert!(
+        result.is_err(),
+        "Should fail when milestones are not completed"
     );
-    assert!(result.is_err());
 }
 
+
 #[test]
-fn test_change_milestone_status_and_flag() {
+fn test_dispute_flag_management() {
     let env = Env::default();
     env.mock_all_auths();
 
@@ -213,248 +3489,257 @@ fn test_change_milestone_status_and_flag() {
     let platform_address = Address::generate(&env);
     let release_signer_address = Address::generate(&env);
     let dispute_resolver_address = Address::generate(&env);
+
     let amount: i128 = 100_000_000;
-    let platform_fee = (0.3 * 10i128.pow(18) as f64) as i128;
+    let platform_fee = 3;
 
-    let initial_milestones = vec![
+    let milestones = vec![
         &env,
         Milestone {
-            description: String::from_str(&env, "Milestone 1"),
-            status: String::from_str(&env, "in-progress"),
-            flag: false,
-        },
-        Milestone {
-            description: String::from_str(&env, "Milestone 2"),
-            status: String::from_str(&env, "in-progress"),
+            description: String::from_str(&env, "First milestone"),
+            status: String::from_str(&env, "Pending"),
+            amount: 100_000_000,
             flag: false,
-        },
+            released: false,
+            dispute_flag: false,
+        }
     ];
 
     let engagement_contract_address = env.register_contract(None, EngagementContract);
     let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &Address::generate(&env));
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
 
-    let engagement_id = String::from_str(&env, "test_engagement");
-    engagement_client.initialize_escrow(
-        &engagement_id.clone(),
-        &client_address,
-        &service_provider_address,
-        &platform_address,
-        &amount,
-        &platform_fee,
-        &initial_milestones,
-        &release_signer_address,
-        &dispute_resolver_address,
-    );
-
-    // Change milestone status (valid case)
-    let new_status = String::from_str(&env, "completed");
-    engagement_client.change_milestone_status(
-        &engagement_id.clone(),
-        &(0 as i128), // Milestone index
-        &new_status,
-        &service_provider_address,
-    );
-
-    // Verify milestone status change
-    let updated_escrow = engagement_client.get_escrow_by_id(&engagement_id);
-    assert_eq!(updated_escrow.milestones.get(0).unwrap().status, new_status);
-
-    // Change milestone flag (valid case)
-    engagement_client.change_milestone_flag(&engagement_id, &(0 as i128), &true, &client_address);
-
-    // Verify milestone flag change
-    let final_escrow = engagement_client.get_escrow_by_id(&engagement_id);
-    assert!(final_escrow.milestones.get(0).unwrap().flag);
+    let engagement_id = String::from_str(&env, "test_dispute");
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee,
+        milestones: milestones.clone(),
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    engagement_client.initialize_escrow(&escrow_properties);
 
-    // Invalid index test
-    let invalid_index = 10 as i128;
-    let new_status = String::from_str(&env, "completed");
+    // Save initial state for later comparison
+    let initial_escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    assert!(!initial_escrow.milestones.get(0).unwrap().dispute_flag);
 
-    // Test for `change_status` with invalid index
-    let result = engagement_client.try_change_milestone_status(
-        &engagement_id,
-        &invalid_index,
-        &new_status,
-        &service_provider_address,
-    );
-    assert!(result.is_err());
+    // Only the client or the service provider can open a dispute
+    let result = engagement_client.try_change_dispute_flag(&engagement_id, &0_i128, &platform_address);
+    assert_eq!(result, Err(Ok(ContractError::OnlyClientOrServiceProviderCanOpenDispute)));
 
-    // Test for `change_flag` with invalid index
-    let result = engagement_client.try_change_milestone_flag(
+    // Test 1: Change dispute flag successfully
+    engagement_client.change_dispute_flag(
         &engagement_id,
-        &invalid_index,
-        &true,
+        &0_i128,
         &client_address,
     );
-    assert!(result.is_err());
-
-    // Invalid Engagement ID test
-    let invalid_engagement_id = String::from_str(&env, "invalid_engagement");
-
-    // Test for `change_status` with invalid engagement ID
-    let result = engagement_client.try_change_milestone_status(
-        &invalid_engagement_id,
-        &(0 as i128),
-        &new_status,
-        &service_provider_address,
-    );
-    assert!(result.is_err());
-
-    // Test for `change_flag` with invalid engagement ID
-    let result = engagement_client.try_change_milestone_flag(
-        &invalid_engagement_id,
-        &(0 as i128),
-        &true,
-        &client_address,
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            client_address.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    engagement_contract_address.clone(),
+                    Symbol::new(&env, "change_dispute_flag"),
+                    (engagement_id.clone(), 0_i128, client_address.clone()).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
     );
-    assert!(result.is_err());
 
-    // Test only authorized party can perform the function
-    let unauthorized_address = Address::generate(&env);
+    // Verify dispute flag changed but nothing else did
+    let disputed_escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    let disputed_milestone = disputed_escrow.milestones.get(0).unwrap();
+    let initial_milestone = initial_escrow.milestones.get(0).unwrap();
+    assert!(disputed_milestone.dispute_flag);
+    assert_eq!(disputed_milestone.amount, initial_milestone.amount);
+    assert_eq!(disputed_milestone.flag, initial_milestone.flag);
+    assert_eq!(disputed_escrow.client, initial_escrow.client);
+    assert_eq!(disputed_escrow.service_provider, initial_escrow.service_provider);
+    assert_eq!(disputed_escrow.amount, initial_escrow.amount);
+    assert_eq!(disputed_escrow.platform_fee, initial_escrow.platform_fee);
 
-    // Test for `change_status` by invalid service provider
-    let result = engagement_client.try_change_milestone_status(
+    // Test 2: Try to change flag when already in dispute
+    let result = engagement_client.try_change_dispute_flag(
         &engagement_id,
-        &(0 as i128),
-        &new_status,
-        &unauthorized_address,
+        &0_i128,
+        &client_address,
     );
     assert!(result.is_err());
 
-    // Test for `change_flag` by invalid client
+    // Test 3: A disputed milestone cannot be approved
     let result = engagement_client.try_change_milestone_flag(
         &engagement_id,
-        &(0 as i128),
+        &0_i128,
         &true,
-        &unauthorized_address,
+        &client_address,
     );
     assert!(result.is_err());
 
-    //Escrow Test with no milestone
-    engagement_client.change_escrow_properties(
+    // Test 4: Try with an invalid milestone index
+    let result = engagement_client.try_change_dispute_flag(
         &engagement_id,
+        &5_i128,
         &client_address,
-        &service_provider_address,
-        &platform_address,
-        &amount,
-        &platform_fee,
-        &vec![&env],
-        &release_signer_address,
-        &dispute_resolver_address,
-    );
-    // Test for `change_status` on escrow with no milestones
-    let result = engagement_client.try_change_milestone_status(
-        &engagement_id,
-        &(0 as i128),
-        &new_status,
-        &service_provider_address,
     );
     assert!(result.is_err());
 
-    // Test for `change_flag` on escrow with no milestones
-    let result = engagement_client.try_change_milestone_flag(
-        &engagement_id,
-        &(0 as i128),
-        &true,
+    // Test 5: Try with non-existent escrow
+    let non_existent_id = String::from_str(&env, "non_existent");
+    let result = engagement_client.try_change_dispute_flag(
+        &non_existent_id,
+        &0_i128,
         &client_address,
     );
     assert!(result.is_err());
 }
 
+
 #[test]
-fn test_distribute_escrow_earnings_successful_flow() {
+fn test_dispute_resolution_process() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let admin = Address::generate(&env);
     let client_address = Address::generate(&env);
     let service_provider_address = Address::generate(&env);
     let platform_address = Address::generate(&env);
     let release_signer_address = Address::generate(&env);
     let dispute_resolver_address = Address::generate(&env);
-    let trustless_work_address = Address::generate(&env);
-
-    let usdc_token = create_usdc_token(&env, &admin);
 
     let amount: i128 = 100_000_000;
-    usdc_token.mint(&client_address, &(amount as i128));
-
-    let platform_fee = 5;
+    let platform_fee = 3;
 
     let milestones = vec![
         &env,
         Milestone {
             description: String::from_str(&env, "First milestone"),
-            status: String::from_str(&env, "Completed"),
-            flag: true,
-        },
-        Milestone {
-            description: String::from_str(&env, "Second milestone"),
-            status: String::from_str(&env, "Completed"),
-            flag: true,
-        },
+            status: String::from_str(&env, "Pending"),
+            amount: 100_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        }
     ];
 
     let engagement_contract_address = env.register_contract(None, EngagementContract);
     let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let token_admin = Address::generate(&env);
+    let token_contract = env.register_contract(None, Token);
+    let token_client = TokenClient::new(&env, &token_contract);
 
-    let engagement_id = String::from_str(&env, "test_escrow_1");
-    engagement_client.initialize_escrow(
+    token_client.initialize(
+        &token_admin,
+        &9,
+        &String::from_str(&env, "USDC"),
+        &String::from_str(&env, "USDC")
+    );
+
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &token_contract);
+
+    let engagement_id = String::from_str(&env, "test_resolution");
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: token_contract.clone(),
+        amount,
+        platform_fee,
+        milestones: milestones.clone(),
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    engagement_client.initialize_escrow(&escrow_properties);
+
+    token_client.mint(&client_address, &amount);
+    engagement_client.fund_escrow(&engagement_id, &client_address, &amount);
+
+    // Verify initial state
+    let escrow_balance = token_client.balance(&engagement_contract_address);
+    assert_eq!(escrow_balance, amount);
+
+    // A milestone that is not in dispute cannot be resolved
+    let result = engagement_client.try_resolving_disputes(
         &engagement_id,
-        &client_address,
-        &service_provider_address,
-        &platform_address,
-        &amount,
-        &platform_fee,
-        &milestones,
-        &release_signer_address,
+        &0_i128,
         &dispute_resolver_address,
+        &40_000_000,
+        &60_000_000
     );
+    assert!(result.is_err());
 
-    usdc_token.mint(&engagement_contract_address, &(amount as i128));
-    
-    engagement_client.distribute_escrow_earnings(
+    // Change dispute flag
+    engagement_client.change_dispute_flag(
         &engagement_id,
-        &release_signer_address,
-        &usdc_token.address,
-        &trustless_work_address,
+        &0_i128,
+        &client_address,
     );
 
-    let total_amount = amount as i128;
-    let trustless_work_commission = ((total_amount * 30) / 10000) as i128;
-    let platform_commission = (total_amount * platform_fee as i128) / 100 as i128;
-    let service_provider_amount =
-        (total_amount - (trustless_work_commission + platform_commission)) as i128;
+    // Verify flag changed
+    let disputed_escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    assert!(disputed_escrow.milestones.get(0).unwrap().dispute_flag);
 
-    assert_eq!(
-        usdc_token.balance(&trustless_work_address),
-        trustless_work_commission,
-        "Trustless Work commission amount is incorrect"
+    // The split must add up to the disputed milestone amount
+    let result = engagement_client.try_resolving_disputes(
+        &engagement_id,
+        &0_i128,
+        &dispute_resolver_address,
+        &40_000_000,
+        &40_000_000
     );
+    assert!(result.is_err());
 
-    assert_eq!(
-        usdc_token.balance(&platform_address),
-        platform_commission,
-        "Platform commission amount is incorrect"
+    let result = engagement_client.try_resolving_disputes(
+        &engagement_id,
+        &0_i128,
+        &dispute_resolver_address,
+        &i128::MAX,
+        &60_000_000
     );
+    assert_eq!(result, Err(Ok(ContractError::ResolutionAmountsMustMatchMilestoneAmount)));
 
-    assert_eq!(
-        usdc_token.balance(&service_provider_address),
-        service_provider_amount,
-        "Service Provider received incorrect amount"
+    // Resolve dispute
+    let client_amount: i128 = 40_000_000;
+    let provider_amount: i128 = 60_000_000;
+
+    engagement_client.resolving_disputes(
+        &engagement_id,
+        &0_i128,
+        &dispute_resolver_address,
+        &client_amount,
+        &provider_amount
     );
 
-    assert_eq!(
-        usdc_token.balance(&engagement_contract_address),
-        0,
-        "Contract should have zero balance after claiming earnings"
-    );
+    // Verify final state
+    let final_escrow_balance = token_client.balance(&engagement_contract_address);
+    assert_eq!(final_escrow_balance, 0);
+
+    let resolved_milestone = engagement_client.get_escrow_by_id(&engagement_id).milestones.get(0).unwrap();
+    assert!(!resolved_milestone.dispute_flag);
+    assert!(resolved_milestone.released);
+
+    // Verify token balances
+    assert_eq!(token_client.balance(&client_address), client_amount);
+    assert_eq!(token_client.balance(&service_provider_address), provider_amount);
 }
 
-//test claim escrow earnings in failure scenarios
-// Scenario 1: Escrow with no milestones:
 #[test]
-fn test_distribute_escrow_earnings_no_milestones() {
+fn test_undisputed_milestones_are_paid_while_another_is_in_dispute() {
     let env = Env::default();
     env.mock_all_auths();
 
@@ -464,100 +3749,188 @@ fn test_distribute_escrow_earnings_no_milestones() {
     let platform_address = Address::generate(&env);
     let release_signer_address = Address::generate(&env);
     let dispute_resolver_address = Address::generate(&env);
+    let trustless_work_address = Address::generate(&env);
 
     let usdc_token = create_usdc_token(&env, &admin);
 
+    let amount: i128 = 100_000_000;
+    let platform_fee = 5;
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            description: String::from_str(&env, "First milestone"),
+            status: String::from_str(&env, "Pending"),
+            amount: 60_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+        Milestone {
+            description: String::from_str(&env, "Second milestone"),
+            status: String::from_str(&env, "Pending"),
+            amount: 40_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+    ];
+
     let engagement_contract_address = env.register_contract(None, EngagementContract);
     let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    initialize_engagement_contract(&env, &engagement_client, &trustless_work_address, &usdc_token.address);
+
+    let engagement_id = String::from_str(&env, "test_partial_dispute");
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee,
+        milestones: milestones.clone(),
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    engagement_client.initialize_escrow(&escrow_properties);
+
+    usdc_token.mint(&client_address, &amount);
+    engagement_client.fund_escrow(&engagement_id, &client_address, &amount);
+
+    // The client disputes the second milestone and approves the first one
+    engagement_client.change_dispute_flag(&engagement_id, &1_i128, &client_address);
+    engagement_client.change_milestone_flag(&engagement_id, &0_i128, &true, &client_address);
 
-    let engagement_id_no_milestones = String::from_str(&env, "test_no_milestones");
-    let amount: i128 = 100_000_000;
-    let platform_fee = (0.3 * 10i128 as f64) as i128;
-
-    engagement_client.initialize_escrow(
-        &engagement_id_no_milestones,
-        &client_address,
-        &service_provider_address,
-        &platform_address,
-        &amount,
-        &platform_fee,
-        &vec![&env], // Empty milestones
+    engagement_client.distribute_escrow_earnings(
+        &engagement_id,
         &release_signer_address,
-        &dispute_resolver_address,
     );
 
-    // Try to claim earnings with no milestones (should fail)
+    let released_amount: i128 = 60_000_000;
+    let trustless_work_commission = (released_amount * 30) / 10000;
+    let platform_commission = (released_amount * platform_fee) / 100;
+    let service_provider_amount = released_amount - trustless_work_commission - platform_commission;
+
+    assert_eq!(usdc_token.balance(&service_provider_address), service_provider_amount);
+    assert_eq!(usdc_token.balance(&engagement_contract_address), 40_000_000);
+
+    let escrow = engagement_client.get_escrow_by_id(&engagement_id);
+    assert!(escrow.milestones.get(0).unwrap().released);
+    assert!(!escrow.milestones.get(1).unwrap().released);
+
+    // Nothing is left to release until the dispute is settled
     let result = engagement_client.try_distribute_escrow_earnings(
-        &engagement_id_no_milestones,
+        &engagement_id,
         &release_signer_address,
-        &usdc_token.address,
-        &platform_address, 
     );
-    assert!(
-        result.is_err(),
-        "Should fail when no milestones are defined"
+    assert!(result.is_err());
+
+    // Only the disputed milestone amount is split by the resolver
+    engagement_client.resolving_disputes(
+        &engagement_id,
+        &1_i128,
+        &dispute_resolver_address,
+        &10_000_000,
+        &30_000_000
     );
+
+    assert_eq!(usdc_token.balance(&client_address), 10_000_000);
+    assert_eq!(usdc_token.balance(&service_provider_address), service_provider_amount + 30_000_000);
+    assert_eq!(usdc_token.balance(&engagement_contract_address), 0);
 }
 
-// Scenario 2: Milestones incomplete
 #[test]
-fn test_distribute_escrow_earnings_milestones_incomplete() {
+fn test_escrow_flow_with_stellar_asset_contract() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let admin = Address::generate(&env);
     let client_address = Address::generate(&env);
     let service_provider_address = Address::generate(&env);
     let platform_address = Address::generate(&env);
     let release_signer_address = Address::generate(&env);
     let dispute_resolver_address = Address::generate(&env);
+    let trustless_work_address = Address::generate(&env);
 
-    let usdc_token = create_usdc_token(&env, &admin);
-
-    let engagement_contract_address = env.register_contract(None, EngagementContract);
-    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
-
-    let engagement_id_incomplete = String::from_str(&env, "test_incomplete_milestones");
-    let milestones_incomplete = vec![
-        &env,
-        Milestone {
-            description: String::from_str(&env, "Incomplete milestone"),
-            status: String::from_str(&env, "Pending"),
-            flag: false,
-        },
-    ];
+    let stellar_asset = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let asset_address = stellar_asset.address();
+    let asset_admin = StellarAssetClient::new(&env, &asset_address);
+    let asset_client = TokenClient::new(&env, &asset_address);
 
     let amount: i128 = 100_000_000;
-    let platform_fee = (0.3 * 10i128.pow(18) as f64) as i128;
+    asset_admin.mint(&client_address, &amount);
 
-    engagement_client.initialize_escrow(
-        &engagement_id_incomplete,
-        &client_address,
-        &service_provider_address,
-        &platform_address,
-        &amount,
-        &platform_fee,
-        &milestones_incomplete,
-        &release_signer_address,
+    let engagement_contract_address = env.register_contract(None, EngagementContract);
+    let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    initialize_engagement_contract(&env, &engagement_client, &trustless_work_address, &asset_address);
+
+    let engagement_id = String::from_str(&env, "stellar_asset");
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: asset_address.clone(),
+        amount,
+        platform_fee: 5,
+        milestones: vec![
+            &env,
+            Milestone {
+                description: String::from_str(&env, "First milestone"),
+                status: String::from_str(&env, "Completed"),
+                amount: 60_000_000,
+                flag: false,
+                released: false,
+                dispute_flag: false,
+            },
+            Milestone {
+                description: String::from_str(&env, "Second milestone"),
+                status: String::from_str(&env, "Pending"),
+                amount: 40_000_000,
+                flag: false,
+                released: false,
+                dispute_flag: false,
+            },
+        ],
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    engagement_client.initialize_escrow(&escrow_properties);
+    engagement_client.change_milestone_flag(&engagement_id, &0, &true, &client_address);
+
+    engagement_client.fund_escrow(&engagement_id, &client_address, &amount);
+    assert_eq!(asset_client.balance(&engagement_contract_address), amount);
+    assert_eq!(engagement_client.get_escrow_balance(&engagement_id).funded, amount);
+
+    engagement_client.distribute_escrow_earnings(&engagement_id, &release_signer_address);
+    assert_eq!(asset_client.balance(&trustless_work_address), 180_000);
+    assert_eq!(asset_client.balance(&platform_address), 3_000_000);
+    assert_eq!(asset_client.balance(&service_provider_address), 56_820_000);
+
+    engagement_client.change_dispute_flag(&engagement_id, &1_i128, &client_address);
+    engagement_client.resolving_disputes(
+        &engagement_id,
+        &1_i128,
         &dispute_resolver_address,
+        &10_000_000,
+        &30_000_000,
     );
-
-    // Try to claim earnings with incomplete milestones (should fail)
-    let result = engagement_client.try_distribute_escrow_earnings(
-        &engagement_id_incomplete,
-        &release_signer_address,
-        &usdc_token.address,
-        &platform_address,
-    );
-    assert!(
-        result.is_err(),
-        "Should fail when milestones are not completed"
-    );
+    assert_eq!(asset_client.balance(&client_address), 10_000_000);
+    assert_eq!(asset_client.balance(&service_provider_address), 86_820_000);
+    assert_eq!(asset_client.balance(&engagement_contract_address), 0);
+    assert_eq!(engagement_client.get_escrow_status(&engagement_id), EscrowStatus::Completed);
 }
 
-
 #[test]
-fn test_dispute_flag_management() {
+fn test_payees_that_cannot_receive_a_stellar_asset() {
     let env = Env::default();
     env.mock_all_auths();
 
@@ -566,145 +3939,218 @@ fn test_dispute_flag_management() {
     let platform_address = Address::generate(&env);
     let release_signer_address = Address::generate(&env);
     let dispute_resolver_address = Address::generate(&env);
+    // A classic account that has no trustline to the asset
+    let account_without_trustline = Address::from_string(&String::from_str(
+        &env,
+        "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF",
+    ));
 
-    let amount: i128 = 100_000_000;
-    let platform_fee = (0.3 * 10i128.pow(18) as f64) as i128;
+    let stellar_asset = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let asset_address = stellar_asset.address();
+    let asset_admin = StellarAssetClient::new(&env, &asset_address);
+    let asset_client = TokenClient::new(&env, &asset_address);
 
-    let milestones = vec![
-        &env,
-        Milestone {
-            description: String::from_str(&env, "First milestone"),
-            status: String::from_str(&env, "Pending"),
-            flag: false,
-        }
-    ];
+    let amount: i128 = 100_000_000;
+    asset_admin.mint(&client_address, &(amount * 3));
 
     let engagement_contract_address = env.register_contract(None, EngagementContract);
     let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
-
-    let engagement_id = String::from_str(&env, "test_dispute");
-    engagement_client.initialize_escrow(
-        &engagement_id,
-        &client_address,
-        &service_provider_address,
-        &platform_address,
-        &amount,
-        &platform_fee,
-        &milestones,
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &asset_address);
+
+    let escrow_properties = Escrow {
+        engagement_id: String::from_str(&env, "no_trustline"),
+        client: client_address.clone(),
+        service_provider: account_without_trustline.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: asset_address.clone(),
+        amount,
+        platform_fee: 5,
+        milestones: vec![
+            &env,
+            Milestone {
+                description: String::from_str(&env, "Only milestone"),
+                status: String::from_str(&env, "Completed"),
+                amount,
+                flag: false,
+                released: false,
+                dispute_flag: false,
+            },
+        ],
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    let deauthorized_escrow = Escrow {
+        engagement_id: String::from_str(&env, "deauthorized"),
+        service_provider: service_provider_address.clone(),
+        ..escrow_properties.clone()
+    };
+    let authorization_required_escrow = Escrow {
+        engagement_id: String::from_str(&env, "authorization_required"),
+        service_provider: Address::generate(&env),
+        ..escrow_properties.clone()
+    };
+
+    for escrow in [&escrow_properties, &deauthorized_escrow, &authorization_required_escrow] {
+        engagement_client.initialize_escrow(escrow);
+        engagement_client.change_milestone_flag(&escrow.engagement_id, &0, &true, &client_address);
+        engagement_client.fund_escrow(&escrow.engagement_id, &client_address, &amount);
+    }
+
+    // Nothing is paid out, and the escrow is left as it was, when the
+    // service provider cannot hold the asset
+    let result = engagement_client.try_distribute_escrow_earnings(
+        &escrow_properties.engagement_id,
         &release_signer_address,
-        &dispute_resolver_address,
     );
-
-    // Save initial state for later comparison
-    let initial_escrow = engagement_client.get_escrow_by_id(&engagement_id);
-    assert_eq!(initial_escrow.dispute_flag, false);
-
-    // Test 1: Change dispute flag successfully
-    engagement_client.change_dispute_flag(
-        &engagement_id,
+    assert_eq!(result, Err(Ok(ContractError::PayeeCannotReceiveAsset)));
+    assert_eq!(asset_client.balance(&platform_address), 0);
+    assert_eq!(engagement_client.get_escrow_balance(&escrow_properties.engagement_id).outstanding, amount);
+    assert!(!engagement_client.get_escrow_by_id(&escrow_properties.engagement_id).milestones.get(0).unwrap().released);
+
+    // A balance frozen by the issuer cannot receive either
+    stellar_asset.issuer().set_flag(IssuerFlags::RevocableFlag);
+    asset_admin.set_authorized(&service_provider_address, &false);
+    let result = engagement_client.try_distribute_escrow_earnings(
+        &deauthorized_escrow.engagement_id,
+        &release_signer_address,
     );
+    assert_eq!(result, Err(Ok(ContractError::PayeeCannotReceiveAsset)));
 
-    // Verify dispute flag changed but nothing else did
-    let disputed_escrow = engagement_client.get_escrow_by_id(&engagement_id);
-    assert_eq!(disputed_escrow.dispute_flag, true);
-    assert_eq!(disputed_escrow.client, initial_escrow.client);
-    assert_eq!(disputed_escrow.service_provider, initial_escrow.service_provider);
-    assert_eq!(disputed_escrow.amount, initial_escrow.amount);
-    assert_eq!(disputed_escrow.platform_fee, initial_escrow.platform_fee);
-    assert_eq!(disputed_escrow.milestones, initial_escrow.milestones);
+    asset_admin.set_authorized(&service_provider_address, &true);
+    engagement_client.distribute_escrow_earnings(&deauthorized_escrow.engagement_id, &release_signer_address);
+    assert_eq!(asset_client.balance(&service_provider_address), 94_700_000);
 
-    // Test 2: Try to change flag when already in dispute
-    let result = engagement_client.try_change_dispute_flag(
-        &engagement_id,
+    // With authorization required, new holders wait for the issuer
+    stellar_asset.issuer().set_flag(IssuerFlags::RequiredFlag);
+    let result = engagement_client.try_distribute_escrow_earnings(
+        &authorization_required_escrow.engagement_id,
+        &release_signer_address,
     );
-    assert!(result.is_err());
+    assert_eq!(result, Err(Ok(ContractError::PayeeCannotReceiveAsset)));
 
-
-    // Test 3: Try with non-existent escrow
-    let non_existent_id = String::from_str(&env, "non_existent");
-    let result = engagement_client.try_change_dispute_flag(
-        &non_existent_id,
+    asset_admin.set_authorized(&authorization_required_escrow.service_provider, &true);
+    engagement_client.distribute_escrow_earnings(
+        &authorization_required_escrow.engagement_id,
+        &release_signer_address,
     );
-    assert!(result.is_err());
+    assert_eq!(asset_client.balance(&authorization_required_escrow.service_provider), 94_700_000);
 }
 
-
 #[test]
-fn test_dispute_resolution_process() {
+fn test_get_balance_and_escrow_balance() {
     let env = Env::default();
     env.mock_all_auths();
 
+    let admin = Address::generate(&env);
     let client_address = Address::generate(&env);
     let service_provider_address = Address::generate(&env);
     let platform_address = Address::generate(&env);
     let release_signer_address = Address::generate(&env);
     let dispute_resolver_address = Address::generate(&env);
+    let trustless_work_address = Address::generate(&env);
+
+    let usdc_token = create_usdc_token(&env, &admin);
 
     let amount: i128 = 100_000_000;
-    let platform_fee = (0.3 * 10i128.pow(18) as f64) as i128;
+    let platform_fee = 5;
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            description: String::from_str(&env, "First milestone"),
+            status: String::from_str(&env, "Completed"),
+            amount: 60_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+        Milestone {
+            description: String::from_str(&env, "Second milestone"),
+            status: String::from_str(&env, "Pending"),
+            amount: 40_000_000,
+            flag: false,
+            released: false,
+            dispute_flag: false,
+        },
+    ];
 
     let engagement_contract_address = env.register_contract(None, EngagementContract);
     let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    initialize_engagement_contract(&env, &engagement_client, &trustless_work_address, &usdc_token.address);
+
+    let engagement_id = String::from_str(&env, "test_balance");
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee,
+        milestones,
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    engagement_client.initialize_escrow(&escrow_properties);
+    engagement_client.change_milestone_flag(&engagement_id, &0, &true, &client_address);
+
+    usdc_token.mint(&client_address, &amount);
+    engagement_client.fund_escrow(&engagement_id, &client_address, &amount);
 
-    let engagement_id = String::from_str(&env, "test_resolution");
-    engagement_client.initialize_escrow(
-        &engagement_id,
-        &client_address,
-        &service_provider_address,
-        &platform_address,
-        &amount,
-        &platform_fee,
-        &vec![&env],
-        &release_signer_address,
-        &dispute_resolver_address,
-    );
-
-    let token_admin = Address::generate(&env);
-    let token_contract = env.register_contract(None, crate::token::token::Token);
-    let token_client = TokenClient::new(&env, &token_contract);
-
-    token_client.initialize(
-        &token_admin,
-        &9,
-        &String::from_str(&env, "USDC"),
-        &String::from_str(&env, "USDC")
+    assert_eq!(
+        engagement_client.get_balance(&engagement_contract_address, &usdc_token.address),
+        amount
     );
 
-    token_client.mint(&token_admin, &(amount as i128));
-    token_client.transfer(&token_admin, &engagement_contract_address, &(amount as i128));
-
-    // Verify initial state
-    let escrow_balance = token_client.balance(&engagement_contract_address);
-    assert_eq!(escrow_balance, amount as i128);
+    let escrow_balance = engagement_client.get_escrow_balance(&engagement_id);
+    assert_eq!(escrow_balance.funded, amount);
+    assert_eq!(escrow_balance.released, 0);
+    assert_eq!(escrow_balance.refunded, 0);
+    assert_eq!(escrow_balance.outstanding, amount);
 
-    // Change dispute flag
-    engagement_client.change_dispute_flag(
+    engagement_client.distribute_escrow_earnings(
         &engagement_id,
+        &release_signer_address,
     );
 
-    // Verify flag changed
-    let disputed_escrow = engagement_client.get_escrow_by_id(&engagement_id);
-    assert_eq!(disputed_escrow.dispute_flag, true);
-
-    // Resolve dispute
-    let client_amount: i128 = 40_000_000;
-    let provider_amount: i128 = 60_000_000;
+    let escrow_balance = engagement_client.get_escrow_balance(&engagement_id);
+    assert_eq!(escrow_balance.released, 60_000_000);
+    assert_eq!(escrow_balance.outstanding, 40_000_000);
 
+    engagement_client.change_dispute_flag(&engagement_id, &1_i128, &client_address);
     engagement_client.resolving_disputes(
         &engagement_id,
+        &1_i128,
         &dispute_resolver_address,
-        &token_contract,
-        &client_amount,
-        &provider_amount
+        &10_000_000,
+        &30_000_000
     );
 
-    // Verify final state
-    let final_escrow_balance = token_client.balance(&engagement_contract_address);
-    assert_eq!(final_escrow_balance, 0);
+    let escrow_balance = engagement_client.get_escrow_balance(&engagement_id);
+    assert_eq!(escrow_balance.funded, amount);
+    assert_eq!(escrow_balance.released, 90_000_000);
+    assert_eq!(escrow_balance.refunded, 10_000_000);
+    assert_eq!(escrow_balance.outstanding, 0);
 
-    // Verify token balances
-    assert_eq!(token_client.balance(&client_address), client_amount as i128);
-    assert_eq!(token_client.balance(&service_provider_address), provider_amount as i128);
+    assert_eq!(
+        engagement_client.get_balance(&engagement_contract_address, &usdc_token.address),
+        0
+    );
+    assert_eq!(
+        engagement_client.get_balance(&client_address, &usdc_token.address),
+        10_000_000
+    );
+
+    let result = engagement_client.try_get_escrow_balance(&String::from_str(&env, "non_existent"));
+    assert!(result.is_err());
 }
 
 #[test]
@@ -719,48 +4165,60 @@ fn test_fund_escrow_successful_deposit() {
     let service_provider_address = Address::generate(&env);
     let release_signer_address = Address::generate(&env);
     let dispute_resolver_address = Address::generate(&env);
-    let platform_fee = (0.3 * 10i128.pow(18) as f64) as i128;
+    let platform_fee = 3;
     let milestones = vec![
         &env,
         Milestone {
             description: String::from_str(&env, "First milestone"),
             status: String::from_str(&env, "Pending"),
+            amount: 50_000_000,
             flag: false,
+            released: false,
+            dispute_flag: false,
         },
         Milestone {
             description: String::from_str(&env, "Second milestone"),
             status: String::from_str(&env, "Pending"),
+            amount: 50_000_000,
             flag: false,
+            released: false,
+            dispute_flag: false,
         },
     ];
 
     let engagement_contract_address = env.register_contract(None, EngagementContract);
     let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &admin);
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
 
     let engagement_id = String::from_str(&env, "12345");
 
-    let engagement_id = engagement_client.initialize_escrow(
-        &engagement_id.clone(),
-        &client_address,
-        &service_provider_address,
-        &platform_address,
-        &amount,
-        &platform_fee,
-        &milestones,
-        &release_signer_address,
-        &dispute_resolver_address,
-    );
-
-    let usdc_token = create_usdc_token(&env, &admin);
-    usdc_token.mint(&engagement_contract_address, &(amount as i128));
-    usdc_token.mint(&release_signer_address, &(amount as i128));
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee,
+        milestones: milestones.clone(),
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    let engagement_id = engagement_client.initialize_escrow(&escrow_properties);
+
+    usdc_token.mint(&engagement_contract_address, &amount);
+    usdc_token.mint(&release_signer_address, &amount);
 
     let amount_to_deposit: i128 = 100_000;
 
     engagement_client.fund_escrow(
         &engagement_id, 
         &release_signer_address, 
-        &usdc_token.address, 
         &amount_to_deposit
     );
 
@@ -785,49 +4243,61 @@ fn test_fund_escrow_fully_funded_error() {
     let service_provider_address = Address::generate(&env);
     let release_signer_address = Address::generate(&env);
     let dispute_resolver_address = Address::generate(&env);
-    let platform_fee = (0.3 * 10i128.pow(18) as f64) as i128;
+    let platform_fee = 3;
     let milestones = vec![
         &env,
         Milestone {
             description: String::from_str(&env, "First milestone"),
             status: String::from_str(&env, "Pending"),
+            amount: 50_000,
             flag: false,
+            released: false,
+            dispute_flag: false,
         },
         Milestone {
             description: String::from_str(&env, "Second milestone"),
             status: String::from_str(&env, "Pending"),
+            amount: 50_000,
             flag: false,
+            released: false,
+            dispute_flag: false,
         },
     ];
 
     let engagement_contract_address = env.register_contract(None, EngagementContract);
     let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &admin);
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
 
     let engagement_id = String::from_str(&env, "12345");
 
-    let engagement_id = engagement_client.initialize_escrow(
-        &engagement_id.clone(),
-        &client_address,
-        &service_provider_address,
-        &platform_address,
-        &amount,
-        &platform_fee,
-        &milestones,
-        &release_signer_address,
-        &dispute_resolver_address,
-    );
-
-    let usdc_token = create_usdc_token(&env, &admin);
-    let funded_amount: i128 = 100_000_000; 
-    usdc_token.mint(&engagement_contract_address, &(funded_amount as i128));
-    usdc_token.mint(&release_signer_address, &(amount as i128));
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee,
+        milestones: milestones.clone(),
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    let engagement_id = engagement_client.initialize_escrow(&escrow_properties);
+
+    usdc_token.mint(&client_address, &amount);
+    engagement_client.fund_escrow(&engagement_id, &client_address, &amount);
+    usdc_token.mint(&release_signer_address, &amount);
 
     let amount_to_deposit: i128 = 100_000;
 
     let result = engagement_client.try_fund_escrow(
         &engagement_id, 
         &release_signer_address, 
-        &usdc_token.address, 
         &amount_to_deposit
     );
 
@@ -849,50 +4319,62 @@ fn test_fund_escrow_signer_insufficient_funds_error() {
     let service_provider_address = Address::generate(&env);
     let release_signer_address = Address::generate(&env);
     let dispute_resolver_address = Address::generate(&env);
-    let platform_fee = (0.3 * 10i128.pow(18) as f64) as i128;
+    let platform_fee = 3;
     let milestones = vec![
         &env,
         Milestone {
             description: String::from_str(&env, "First milestone"),
             status: String::from_str(&env, "Pending"),
+            amount: 50_000_000,
             flag: false,
+            released: false,
+            dispute_flag: false,
         },
         Milestone {
             description: String::from_str(&env, "Second milestone"),
             status: String::from_str(&env, "Pending"),
+            amount: 50_000_000,
             flag: false,
+            released: false,
+            dispute_flag: false,
         },
     ];
 
     let engagement_contract_address = env.register_contract(None, EngagementContract);
     let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &admin);
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
 
     let engagement_id = String::from_str(&env, "12345");
 
-    let engagement_id = engagement_client.initialize_escrow(
-        &engagement_id.clone(),
-        &client_address,
-        &service_provider_address,
-        &platform_address,
-        &amount,
-        &platform_fee,
-        &milestones,
-        &release_signer_address,
-        &dispute_resolver_address,
-    );
-
-    let usdc_token = create_usdc_token(&env, &admin);
-    usdc_token.mint(&engagement_contract_address, &(amount as i128));
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee,
+        milestones: milestones.clone(),
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    let engagement_id = engagement_client.initialize_escrow(&escrow_properties);
+
+    usdc_token.mint(&engagement_contract_address, &amount);
 
     let signer_funds: i128 = 100_000; 
-    usdc_token.mint(&release_signer_address, &(signer_funds as i128));
+    usdc_token.mint(&release_signer_address, &signer_funds);
 
     let amount_to_deposit: i128 = 180_000;
 
     let result = engagement_client.try_fund_escrow(
         &engagement_id, 
         &release_signer_address, 
-        &usdc_token.address, 
         &amount_to_deposit
     );
 
@@ -904,7 +4386,7 @@ fn test_fund_escrow_signer_insufficient_funds_error() {
 
 
 #[test]
-fn test_fund_escrow_dispute_flag_error() {
+fn test_fund_escrow_with_milestone_in_dispute() {
     let env = Env::default();
     env.mock_all_auths();
 
@@ -915,57 +4397,97 @@ fn test_fund_escrow_dispute_flag_error() {
     let service_provider_address = Address::generate(&env);
     let release_signer_address = Address::generate(&env);
     let dispute_resolver_address = Address::generate(&env);
-    let platform_fee = (0.3 * 10i128.pow(18) as f64) as i128;
+    let platform_fee = 3;
     let milestones = vec![
         &env,
         Milestone {
             description: String::from_str(&env, "First milestone"),
             status: String::from_str(&env, "Pending"),
+            amount: 50_000_000,
             flag: false,
+            released: false,
+            dispute_flag: false,
         },
         Milestone {
             description: String::from_str(&env, "Second milestone"),
             status: String::from_str(&env, "Pending"),
+            amount: 50_000_000,
             flag: false,
+            released: false,
+            dispute_flag: false,
         },
     ];
 
     let engagement_contract_address = env.register_contract(None, EngagementContract);
     let engagement_client = EngagementContractClient::new(&env, &engagement_contract_address);
+    let usdc_token = create_usdc_token(&env, &admin);
+    initialize_engagement_contract(&env, &engagement_client, &Address::generate(&env), &usdc_token.address);
 
     let engagement_id = String::from_str(&env, "12345");
 
-    let engagement_id = engagement_client.initialize_escrow(
-        &engagement_id.clone(),
-        &client_address,
-        &service_provider_address,
-        &platform_address,
-        &amount,
-        &platform_fee,
-        &milestones,
-        &release_signer_address,
-        &dispute_resolver_address,
-    );
-
-    let usdc_token = create_usdc_token(&env, &admin);
-    usdc_token.mint(&engagement_contract_address, &(amount as i128));
-    usdc_token.mint(&release_signer_address, &(amount as i128));
+    let escrow_properties = Escrow {
+        engagement_id: engagement_id.clone(),
+        client: client_address.clone(),
+        service_provider: service_provider_address.clone(),
+        approver: None,
+        platform_address: platform_address.clone(),
+        asset: usdc_token.address.clone(),
+        amount,
+        platform_fee,
+        milestones: milestones.clone(),
+        release_signers: vec![&env, release_signer_address.clone()],
+        release_threshold: 1,
+        dispute_resolver: dispute_resolver_address.clone(),
+        mode: EscrowMode::Milestones,
+        challenge_period: 0,
+    };
+    let engagement_id = engagement_client.initialize_escrow(&escrow_properties);
+
+    usdc_token.mint(&engagement_contract_address, &amount);
+    usdc_token.mint(&release_signer_address, &amount);
 
     engagement_client.change_dispute_flag(
         &engagement_id,
+        &0_i128,
+        &client_address,
     );
 
     let amount_to_deposit: i128 = 80_000;
 
-    let result = engagement_client.try_fund_escrow(
+    // A dispute on one milestone no longer freezes the whole escrow
+    engagement_client.fund_escrow(
         &engagement_id, 
         &release_signer_address, 
-        &usdc_token.address, 
         &amount_to_deposit
     );
 
-    assert!(
-        result.is_err(),
-        "Should fail when the dispute flag is true"
+    assert_eq!(
+        usdc_token.balance(&engagement_contract_address),
+        amount + amount_to_deposit,
+        "Escrow balance is incorrect"
     );
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_error_codes_shared_with_the_token() {
+    // Codes raised by both contracts mean the same thing in both
+    for (contract_error, token_error) in [
+        (ContractError::ContractAlreadyInitialized, TokenError::ContractAlreadyInitialized),
+        (ContractError::ContractNotInitialized, TokenError::ContractNotInitialized),
+        (ContractError::NegativeAmountNotAllowed, TokenError::NegativeAmountNotAllowed),
+    ] {
+        assert_eq!(contract_error as u32, token_error as u32);
+    }
+
+    // Codes that belong to the token are not given to engagement errors
+    for token_error in [
+        TokenError::NotEnoughAllowance,
+        TokenError::InsufficientBalance,
+        TokenError::InvalidDecimal,
+        TokenError::InvalidExpirationLedger,
+        TokenError::AccountNotAuthorized,
+    ] {
+        let error = soroban_sdk::Error::from_contract_error(token_error as u32);
+        assert!(ContractError::try_from(error).is_err());
+    }
+}
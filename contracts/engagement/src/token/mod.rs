@@ -1,4 +0,0 @@
-pub mod allowance;
-pub mod balance;
-pub mod metadata;
-pub mod token;
\ No newline at end of file
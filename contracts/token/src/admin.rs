@@ -1,21 +1,22 @@
 use soroban_sdk::{Address, Env};
 
-use crate::storage::types::DataKey;
+use crate::storage_types::DataKey;
+use crate::error::TokenError;
 
 pub fn has_administrator(e: &Env) -> bool {
     let key = DataKey::Admin;
     e.storage().instance().has(&key)
 }
 
-pub fn read_administrator(e: &Env) -> Address {
+pub fn read_administrator(e: &Env) -> Result<Address, TokenError> {
     let key = DataKey::Admin;
-    e.storage().instance().get(&key).unwrap()
+    e.storage()
+        .instance()
+        .get(&key)
+        .ok_or(TokenError::ContractNotInitialized)
 }
 
 pub fn write_administrator(e: &Env, id: &Address) {
     let key = DataKey::Admin;
     e.storage().instance().set(&key, id);
-
-    let stored_admin = read_administrator(&e);
-    assert_eq!(stored_admin, *id);
-}
\ No newline at end of file
+}
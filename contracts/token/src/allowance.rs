@@ -1,5 +1,6 @@
-use crate::storage::types::{AllowanceDataKey, AllowanceValue, DataKey};
-use soroban_sdk::{Address, Env};
+use crate::storage_types::{AllowanceDataKey, AllowanceValue, DataKey};
+use crate::error::TokenError;
+use soroban_sdk::{panic_with_error, Address, Env};
 
 pub fn read_allowance(e: &Env, from: Address, spender: Address) -> AllowanceValue {
     let key = DataKey::Allowance(AllowanceDataKey { from, spender });
@@ -33,16 +34,14 @@ pub fn write_allowance(
     };
 
     if amount > 0 && expiration_ledger < e.ledger().sequence() {
-        panic!("expiration_ledger is less than ledger seq when amount > 0")
+        panic_with_error!(e, TokenError::InvalidExpirationLedger);
     }
 
     let key = DataKey::Allowance(AllowanceDataKey { from, spender });
     e.storage().persistent().set(&key.clone(), &allowance);
 
     if amount > 0 {
-        let live_for = expiration_ledger
-            .checked_sub(e.ledger().sequence())
-            .unwrap();
+        let live_for = expiration_ledger - e.ledger().sequence();
 
         e.storage().persistent().extend_ttl(&key, live_for, live_for)
     }
@@ -51,7 +50,7 @@ pub fn write_allowance(
 pub fn spend_allowance(e: &Env, from: Address, spender: Address, amount: i128) {
     let allowance = read_allowance(e, from.clone(), spender.clone());
     if allowance.amount < amount {
-        panic!("insufficient allowance");
+        panic_with_error!(e, TokenError::NotEnoughAllowance);
     }
     if amount > 0 {
         write_allowance(
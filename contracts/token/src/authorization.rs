@@ -0,0 +1,41 @@
+use crate::storage_types::DataKey;
+use crate::error::TokenError;
+use soroban_sdk::{panic_with_error, Address, Env};
+
+const AUTHORIZATION_BUMP_AMOUNT: u32 = 1000;
+const AUTHORIZATION_LIFETIME_THRESHOLD: u32 = 10;
+
+pub fn read_authorized(e: &Env, addr: Address) -> bool {
+    let key = DataKey::Authorized(addr);
+    if let Some(authorized) = e.storage().persistent().get::<DataKey, bool>(&key) {
+        e.storage().persistent().extend_ttl(
+            &key,
+            AUTHORIZATION_LIFETIME_THRESHOLD,
+            AUTHORIZATION_BUMP_AMOUNT,
+        );
+        authorized
+    } else {
+        true
+    }
+}
+
+pub fn write_authorized(e: &Env, addr: Address, authorize: bool) {
+    let key = DataKey::Authorized(addr);
+    if authorize {
+        // Accounts are authorized unless frozen, so only frozen ones are stored
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage().persistent().set(&key, &authorize);
+        e.storage().persistent().extend_ttl(
+            &key,
+            AUTHORIZATION_LIFETIME_THRESHOLD,
+            AUTHORIZATION_BUMP_AMOUNT,
+        );
+    }
+}
+
+pub fn check_authorized(e: &Env, addr: Address) {
+    if !read_authorized(e, addr) {
+        panic_with_error!(e, TokenError::AccountNotAuthorized);
+    }
+}
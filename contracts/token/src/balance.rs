@@ -1,6 +1,7 @@
 #![allow(dead_code)]
-use crate::storage::types::DataKey;
-use soroban_sdk::{Address, Env};
+use crate::storage_types::DataKey;
+use crate::error::TokenError;
+use soroban_sdk::{panic_with_error, Address, Env};
 
 const BALANCE_BUMP_AMOUNT: u32 = 1000;
 const BALANCE_LIFETIME_THRESHOLD: u32 = 10;
@@ -33,7 +34,7 @@ pub fn receive_balance(e: &Env, addr: Address, amount: i128) {
 pub fn spend_balance(e: &Env, addr: Address, amount: i128) {
     let balance = read_balance(e, addr.clone());
     if balance < amount {
-        panic!("insufficient balance");
+        panic_with_error!(e, TokenError::InsufficientBalance);
     }
     write_balance(e, addr, balance - amount);
 }
@@ -0,0 +1,248 @@
+use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Env, String};
+use soroban_sdk::token::{self, Interface as _};
+use soroban_token_sdk::metadata::TokenMetadata;
+
+use crate::admin::{has_administrator, write_administrator, read_administrator};
+use crate::allowance::{read_allowance, write_allowance, spend_allowance};
+use crate::authorization::{check_authorized, read_authorized, write_authorized};
+use crate::balance::{receive_balance, read_balance, spend_balance};
+use crate::storage_types::{INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD};
+use crate::error::TokenError;
+use crate::metadata::{read_decimal, read_name, read_symbol, write_metadata};
+use soroban_token_sdk::TokenUtils;
+
+fn check_nonnegative_amount(e: &Env, amount: i128) {
+    if amount < 0 {
+        panic_with_error!(e, TokenError::NegativeAmountNotAllowed);
+    }
+}
+
+fn check_initialized(e: &Env) {
+    if !has_administrator(e) {
+        panic_with_error!(e, TokenError::ContractNotInitialized);
+    }
+}
+
+#[contract]
+pub struct Token;
+
+#[contractimpl]
+impl Token {
+    pub fn initialize(
+        e: Env,
+        admin: Address,
+        decimal: u32,
+        name: String,
+        symbol: String,
+    ) -> Result<(), TokenError> {
+        if has_administrator(&e) {
+            return Err(TokenError::ContractAlreadyInitialized);
+        }
+        if decimal > 18 {
+            return Err(TokenError::InvalidDecimal);
+        }
+        write_administrator(&e, &admin);
+    
+        let metadata = TokenMetadata {
+            decimal,
+            name: name.clone(),
+            symbol: symbol.clone(),
+        };
+    
+        write_metadata(&e, metadata);
+
+        Ok(())
+    }
+
+    pub fn mint(e: Env, to: Address, amount: i128) -> Result<(), TokenError> {
+        if amount < 0 {
+            return Err(TokenError::NegativeAmountNotAllowed);
+        }
+        
+        let admin = read_administrator(&e)?;
+        admin.require_auth();
+
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        if !read_authorized(&e, to.clone()) {
+            return Err(TokenError::AccountNotAuthorized);
+        }
+
+        receive_balance(&e, to.clone(), amount);
+        TokenUtils::new(&e).events().mint(admin, to, amount);
+
+        Ok(())
+    }
+
+    pub fn set_admin(e: Env, new_admin: Address) -> Result<(), TokenError> {
+        let admin = read_administrator(&e)?;
+        admin.require_auth();
+
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        write_administrator(&e, &new_admin);
+        TokenUtils::new(&e).events().set_admin(admin, new_admin);
+
+        Ok(())
+    }
+
+    /// Freezes (`authorize = false`) or unfreezes an account. A frozen
+    /// account can neither send nor receive the token.
+    pub fn set_authorized(e: Env, id: Address, authorize: bool) -> Result<(), TokenError> {
+        let admin = read_administrator(&e)?;
+        admin.require_auth();
+
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        write_authorized(&e, id.clone(), authorize);
+        TokenUtils::new(&e).events().set_authorized(admin, id, authorize);
+
+        Ok(())
+    }
+
+    pub fn authorized(e: Env, id: Address) -> bool {
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        read_authorized(&e, id)
+    }
+
+    /// Burns `amount` from `from` on behalf of the admin, including from
+    /// frozen accounts.
+    pub fn clawback(e: Env, from: Address, amount: i128) -> Result<(), TokenError> {
+        if amount < 0 {
+            return Err(TokenError::NegativeAmountNotAllowed);
+        }
+
+        let admin = read_administrator(&e)?;
+        admin.require_auth();
+
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        spend_balance(&e, from.clone(), amount);
+        TokenUtils::new(&e).events().clawback(admin, from, amount);
+
+        Ok(())
+    }
+}
+
+#[contractimpl]
+impl token::Interface for Token {
+    fn allowance(e: Env, from: Address, spender: Address) -> i128 {
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        read_allowance(&e, from, spender).amount
+    }
+
+    fn approve(e: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+        from.require_auth();
+
+        check_nonnegative_amount(&e, amount);
+
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        write_allowance(&e, from.clone(), spender.clone(), amount, expiration_ledger);
+        TokenUtils::new(&e)
+            .events()
+            .approve(from, spender, amount, expiration_ledger);
+    }    
+
+    fn balance(e: Env, id: Address) -> i128 {
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        read_balance(&e, id)
+    }
+
+    fn transfer(e: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+
+        check_nonnegative_amount(&e, amount);
+
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        check_authorized(&e, from.clone());
+        check_authorized(&e, to.clone());
+
+        spend_balance(&e, from.clone(), amount);
+        receive_balance(&e, to.clone(), amount);
+        TokenUtils::new(&e).events().transfer(from, to, amount);
+    }
+
+    fn transfer_from(e: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        spender.require_auth();
+
+        check_nonnegative_amount(&e, amount);
+
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        check_authorized(&e, from.clone());
+        check_authorized(&e, to.clone());
+
+        spend_allowance(&e, from.clone(), spender, amount);
+        spend_balance(&e, from.clone(), amount);
+        receive_balance(&e, to.clone(), amount);
+        TokenUtils::new(&e).events().transfer(from, to, amount)
+    }
+
+    fn burn(e: Env, from: Address, amount: i128) {
+        from.require_auth();
+
+        check_nonnegative_amount(&e, amount);
+
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        check_authorized(&e, from.clone());
+
+        spend_balance(&e, from.clone(), amount);
+        TokenUtils::new(&e).events().burn(from, amount);
+    }
+
+    fn burn_from(e: Env, spender: Address, from: Address, amount: i128) {
+        spender.require_auth();
+
+        check_nonnegative_amount(&e, amount);
+
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        check_authorized(&e, from.clone());
+
+        spend_allowance(&e, from.clone(), spender, amount);
+        spend_balance(&e, from.clone(), amount);
+        TokenUtils::new(&e).events().burn(from, amount)
+    }
+
+    fn decimals(e: Env) -> u32 {
+        check_initialized(&e);
+        read_decimal(&e)
+    }
+
+    fn name(e: Env) -> String {
+        check_initialized(&e);
+        read_name(&e)
+    }
+
+    fn symbol(e: Env) -> String {
+        check_initialized(&e);
+        read_symbol(&e)
+    }
+}
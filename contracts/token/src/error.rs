@@ -0,0 +1,33 @@
+use soroban_sdk::contracterror;
+use core::fmt;
+
+/// Errors of the [`Token`](crate::Token) contract. The codes are shared with
+/// the engagement contract's `ContractError` so a code means the same thing
+/// in both contracts.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[contracterror]
+pub enum TokenError {
+    NotEnoughAllowance = 8,
+    ContractAlreadyInitialized = 45,
+    ContractNotInitialized = 46,
+    NegativeAmountNotAllowed = 54,
+    InsufficientBalance = 55,
+    InvalidDecimal = 56,
+    InvalidExpirationLedger = 57,
+    AccountNotAuthorized = 62,
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenError::NotEnoughAllowance => write!(f, "Not enough allowance"),
+            TokenError::ContractAlreadyInitialized => write!(f, "The contract has already been initialized"),
+            TokenError::ContractNotInitialized => write!(f, "The contract has not been initialized"),
+            TokenError::NegativeAmountNotAllowed => write!(f, "Negative amounts are not allowed"),
+            TokenError::InsufficientBalance => write!(f, "Insufficient balance"),
+            TokenError::InvalidDecimal => write!(f, "Decimal must not be greater than 18"),
+            TokenError::InvalidExpirationLedger => write!(f, "The expiration ledger is less than the current ledger"),
+            TokenError::AccountNotAuthorized => write!(f, "The account is not authorized to hold or move this token"),
+        }
+    }
+}
\ No newline at end of file
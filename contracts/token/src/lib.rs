@@ -0,0 +1,14 @@
+#![no_std]
+
+mod admin;
+mod allowance;
+mod authorization;
+mod balance;
+mod contract;
+mod error;
+mod metadata;
+mod storage_types;
+mod tests;
+
+pub use crate::contract::{Token, TokenClient};
+pub use crate::error::TokenError;
\ No newline at end of file
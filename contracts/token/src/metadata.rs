@@ -6,8 +6,6 @@ pub fn write_metadata(e: &Env, metadata: TokenMetadata) {
     util.metadata().set_metadata(&metadata);
 }
 
-// They are not used at the moment 👇
-
 pub fn read_decimal(e: &Env) -> u32 {
     let util = TokenUtils::new(e);
     util.metadata().get_metadata().decimal
@@ -22,9 +20,3 @@ pub fn read_symbol(e: &Env) -> String {
     let util = TokenUtils::new(e);
     util.metadata().get_metadata().symbol
 }
-
-
-pub fn read_metadata(e: &Env) -> TokenMetadata {
-    let util = TokenUtils::new(e);
-    util.metadata().get_metadata()
-}
\ No newline at end of file
@@ -0,0 +1,2 @@
+#[cfg(test)]
+mod test;
\ No newline at end of file
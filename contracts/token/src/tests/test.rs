@@ -0,0 +1,351 @@
+#![cfg(test)]
+
+extern crate std;
+
+use crate::TokenError;
+use crate::{Token, TokenClient};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Events, Ledger},
+    Address, Env, IntoVal, String, Symbol,
+};
+
+fn create_token<'a>(e: &Env, admin: &Address) -> TokenClient<'a> {
+    let token = TokenClient::new(e, &e.register_contract(None, Token {}));
+    token.initialize(admin, &7, &"USDC".into_val(e), &"USDC".into_val(e));
+    token
+}
+
+#[test]
+fn test_metadata() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let token = TokenClient::new(&env, &env.register_contract(None, Token {}));
+    token.initialize(
+        &admin,
+        &9,
+        &String::from_str(&env, "USD Coin"),
+        &String::from_str(&env, "USDC"),
+    );
+
+    assert_eq!(token.decimals(), 9);
+    assert_eq!(token.name(), String::from_str(&env, "USD Coin"));
+    assert_eq!(token.symbol(), String::from_str(&env, "USDC"));
+
+    // Metadata of a token that was never initialized is an error, not a trap
+    let uninitialized_token = TokenClient::new(&env, &env.register_contract(None, Token {}));
+    assert_eq!(
+        uninitialized_token.try_name(),
+        Err(Ok(TokenError::ContractNotInitialized.into()))
+    );
+    assert_eq!(
+        uninitialized_token.try_decimals(),
+        Err(Ok(TokenError::ContractNotInitialized.into()))
+    );
+}
+
+#[test]
+fn test_mint_transfer_and_balance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let token = create_token(&env, &admin);
+
+    assert_eq!(token.balance(&user1), 0);
+
+    token.mint(&user1, &1_000);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    token.address.clone(),
+                    symbol_short!("mint"),
+                    (&user1, 1_000_i128).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    assert_eq!(token.balance(&user1), 1_000);
+
+    token.transfer(&user1, &user2, &600);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            user1.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    token.address.clone(),
+                    symbol_short!("transfer"),
+                    (&user1, &user2, 600_i128).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (
+            symbol_short!("transfer"),
+            user1.clone(),
+            user2.clone(),
+        ).into_val(&env)
+    );
+    let transferred: i128 = data.into_val(&env);
+    assert_eq!(transferred, 600);
+
+    assert_eq!(token.balance(&user1), 400);
+    assert_eq!(token.balance(&user2), 600);
+}
+
+#[test]
+fn test_allowance_and_transfer_from() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let user3 = Address::generate(&env);
+    let token = create_token(&env, &admin);
+
+    token.mint(&user1, &1_000);
+
+    token.approve(&user2, &user3, &500, &200);
+    assert_eq!(token.allowance(&user2, &user3), 500);
+
+    token.approve(&user1, &user3, &500, &200);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            user1.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    token.address.clone(),
+                    symbol_short!("approve"),
+                    (&user1, &user3, 500_i128, 200_u32).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    assert_eq!(token.allowance(&user1, &user3), 500);
+
+    token.transfer_from(&user3, &user1, &user2, &400);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            user3.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    token.address.clone(),
+                    Symbol::new(&env, "transfer_from"),
+                    (&user3, &user1, &user2, 400_i128).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    assert_eq!(token.balance(&user1), 600);
+    assert_eq!(token.balance(&user2), 400);
+    assert_eq!(token.allowance(&user1, &user3), 100);
+
+    assert_eq!(
+        token.try_transfer_from(&user3, &user1, &user2, &101),
+        Err(Ok(TokenError::NotEnoughAllowance.into()))
+    );
+
+    // An expired allowance cannot be spent
+    env.ledger().with_mut(|li| li.sequence_number = 201);
+    assert_eq!(token.allowance(&user1, &user3), 0);
+    assert_eq!(
+        token.try_transfer_from(&user3, &user1, &user2, &1),
+        Err(Ok(TokenError::NotEnoughAllowance.into()))
+    );
+}
+
+#[test]
+fn test_burn_and_burn_from() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let token = create_token(&env, &admin);
+
+    token.mint(&user1, &1_000);
+
+    token.burn(&user1, &100);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            user1.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    token.address.clone(),
+                    symbol_short!("burn"),
+                    (&user1, 100_i128).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    assert_eq!(token.balance(&user1), 900);
+
+    token.approve(&user1, &user2, &500, &200);
+    token.burn_from(&user2, &user1, &500);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            user2.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    token.address.clone(),
+                    symbol_short!("burn_from"),
+                    (&user2, &user1, 500_i128).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    assert_eq!(token.balance(&user1), 400);
+    assert_eq!(token.allowance(&user1, &user2), 0);
+
+    assert_eq!(
+        token.try_burn(&user1, &401),
+        Err(Ok(TokenError::InsufficientBalance.into()))
+    );
+}
+
+#[test]
+fn test_token_failures_return_typed_errors() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    let token = create_token(&env, &admin);
+    token.mint(&user1, &1_000);
+
+    assert_eq!(
+        token.try_initialize(&admin, &7, &"USDC".into_val(&env), &"USDC".into_val(&env)),
+        Err(Ok(TokenError::ContractAlreadyInitialized))
+    );
+    assert_eq!(
+        token.try_mint(&user1, &-1),
+        Err(Ok(TokenError::NegativeAmountNotAllowed))
+    );
+    assert_eq!(
+        token.try_transfer(&user1, &user2, &-1),
+        Err(Ok(TokenError::NegativeAmountNotAllowed.into()))
+    );
+    assert_eq!(
+        token.try_transfer(&user1, &user2, &1_001),
+        Err(Ok(TokenError::InsufficientBalance.into()))
+    );
+    assert_eq!(
+        token.try_transfer_from(&user2, &user1, &user2, &100),
+        Err(Ok(TokenError::NotEnoughAllowance.into()))
+    );
+
+    env.ledger().with_mut(|li| li.sequence_number = 100);
+    assert_eq!(
+        token.try_approve(&user1, &user2, &100, &99),
+        Err(Ok(TokenError::InvalidExpirationLedger.into()))
+    );
+
+    let uninitialized_token = TokenClient::new(&env, &env.register_contract(None, Token {}));
+    assert_eq!(
+        uninitialized_token.try_mint(&user1, &100),
+        Err(Ok(TokenError::ContractNotInitialized))
+    );
+    let result = TokenClient::new(&env, &env.register_contract(None, Token {}))
+        .try_initialize(&admin, &19, &"USDC".into_val(&env), &"USDC".into_val(&env));
+    assert_eq!(result, Err(Ok(TokenError::InvalidDecimal)));
+}
+
+#[test]
+fn test_token_admin_operations() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    let token = create_token(&env, &admin);
+    token.mint(&user1, &1_000);
+    token.mint(&user2, &1_000);
+
+    // A frozen account can neither send nor receive
+    assert!(token.authorized(&user1));
+    token.set_authorized(&user1, &false);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    token.address.clone(),
+                    Symbol::new(&env, "set_authorized"),
+                    (user1.clone(), false).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    assert!(!token.authorized(&user1));
+    assert_eq!(
+        token.try_transfer(&user1, &user2, &100),
+        Err(Ok(TokenError::AccountNotAuthorized.into()))
+    );
+    assert_eq!(
+        token.try_transfer(&user2, &user1, &100),
+        Err(Ok(TokenError::AccountNotAuthorized.into()))
+    );
+    assert_eq!(
+        token.try_mint(&user1, &100),
+        Err(Ok(TokenError::AccountNotAuthorized))
+    );
+
+    // Clawback works on frozen accounts
+    token.clawback(&user1, &400);
+    assert_eq!(token.balance(&user1), 600);
+    assert_eq!(
+        token.try_clawback(&user1, &-1),
+        Err(Ok(TokenError::NegativeAmountNotAllowed))
+    );
+
+    token.set_authorized(&user1, &true);
+    token.transfer(&user1, &user2, &100);
+    assert_eq!(token.balance(&user2), 1_100);
+
+    // The new admin takes over the admin operations
+    token.set_admin(&new_admin);
+    token.mint(&user1, &50);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            new_admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    token.address.clone(),
+                    Symbol::new(&env, "mint"),
+                    (user1.clone(), 50_i128).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    assert_eq!(token.balance(&user1), 550);
+}